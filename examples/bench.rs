@@ -0,0 +1,76 @@
+//! Compares the running time of the equivalence checks on large random automata.
+//!
+//! Run with `cargo run --release --example bench [states]`, defaulting to 100000 states.
+extern crate automata;
+
+use std::env;
+use std::time::{Duration, Instant};
+
+use automata::dfa::Dfa;
+
+/// A xorshift generator, so that runs are comparable and need no dependencies.
+struct Random(u64);
+
+impl Random {
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+fn main() {
+    let states = env::args().nth(1)
+        .map(|arg| arg.parse().expect("Number of states must be an integer"))
+        .unwrap_or(100_000);
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+
+    let edges = (0..states)
+        .flat_map(|from| vec![(from, '0'), (from, '1')])
+        .map(|(from, symbol)| (from, symbol, random.below(states)))
+        .collect::<Vec<_>>();
+    let finals = (0..states)
+        .filter(|_| random.below(2) == 0)
+        .collect::<Vec<_>>();
+
+    // Every state is duplicated and each edge leads into a random copy of its target, which does
+    // not change the language but the structure.
+    let doubled_edges = edges.iter()
+        .flat_map(|&(from, symbol, to)| vec![
+            (from, symbol, to + states * random.below(2)),
+            (from + states, symbol, to + states * random.below(2)),
+        ])
+        .collect::<Vec<_>>();
+    let doubled_finals = finals.iter()
+        .flat_map(|&fin| vec![fin, fin + states])
+        .collect::<Vec<_>>();
+
+    let automaton = Dfa::from_edges(edges, finals);
+    let doubled = Dfa::from_edges(doubled_edges, doubled_finals);
+    let complement = automaton.complement();
+
+    println!("Random automaton with {} states, equivalent one with {} states", states, 2*states);
+    compare("equivalent", &automaton, &doubled);
+    compare("complement", &automaton, &complement);
+}
+
+fn compare(name: &str, lhs: &Dfa<char>, rhs: &Dfa<char>) {
+    let (pair_empty, product) = time(|| lhs.pair_empty(rhs, |lhs, rhs| lhs != rhs));
+    let (hopcroft_karp, union_find) = time(|| lhs.equivalent_hopcroft_karp(rhs));
+    let (witness, union_find_witness) = time(|| lhs.equivalent_hopcroft_karp_witness(rhs).is_ok());
+
+    assert_eq!(pair_empty, hopcroft_karp);
+    assert_eq!(pair_empty, witness);
+
+    println!("{} ({}):", name, pair_empty);
+    println!("  pair_empty                        {:?}", product);
+    println!("  equivalent_hopcroft_karp          {:?}", union_find);
+    println!("  equivalent_hopcroft_karp_witness  {:?}", union_find_witness);
+}
+
+fn time<T, F: FnOnce() -> T>(f: F) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
@@ -6,6 +6,7 @@ use std::process;
 
 use automata::dfa::Dfa;
 use automata::nfa::Nfa;
+use automata::regex::Printer;
 
 fn main() {
     fs::create_dir_all("./output")
@@ -46,7 +47,7 @@ fn nfa() {
     automaton.write_to(&mut output).unwrap();
     fs::write("./output/nfa.dot", output)
         .expect("Failed to write dfa dot file");
-    eprintln!("As regex: {}", automaton.to_regex().to_string());
+    eprintln!("As regex: {}", Printer::default().print(&automaton.to_regex()));
 }
 
 // Try to run `dot` for all files to convert to png, optionally.
@@ -56,14 +57,13 @@ fn convert() {
         .filter_map(|path| path.ok())
         .map(|entry| entry.path())
         .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("dot"))
-        .map(|path| process::Command::new("dot")
+        .try_for_each(|path| process::Command::new("dot")
              .arg(path)
              .arg("-Tpng")
              .arg("-O")
              .spawn()
              .and_then(|mut child| child.wait())
-             .map(|_exit| ()))
-        .collect();
+             .map(|_exit| ()));
 }
 
 fn view() {
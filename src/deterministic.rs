@@ -10,9 +10,10 @@ use std::io::{self, Write};
 use std::num::NonZeroUsize;
 use std::ops::{Index, IndexMut, Range};
 
-use crate::Alphabet;
+use crate::{Alphabet, Error};
 use crate::dot::{Edge, Family, GraphWriter};
 
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Deterministic<A> {
     /// Characters of the underlying alphabet.
     alphabet: Vec<A>,
@@ -93,15 +94,22 @@ impl<A: Alphabet> Deterministic<A> {
     /// # Panics
     /// When the new node id can not be represented.
     pub fn node(&mut self) -> Target {
-        let count = self.char_count();
-        self.edges.extend(iter::repeat(None).take(count));
+        self.try_node().expect("Maximum node count exceeded")
+    }
+
+    /// Create a new node in the graph, unless its id or its edges can not be represented.
+    pub fn try_node(&mut self) -> Result<Target, Error<A>> {
         let id = self.next_id;
+        let target = Target::new(id).ok_or(Error::StateOutOfRange(id))?;
+        let count = self.char_count();
+        self.edges.try_reserve(count).map_err(|_| Error::StateOutOfRange(id))?;
+        self.edges.extend(iter::repeat_n(None, count));
         self.next_id += 1;
-        Target::new(id).expect("Maximum node count exceeded")
+        Ok(target)
     }
 
     /// Get the outgoing edges of a node.
-    pub fn edges(&self, target: Target) -> Option<Edges<A>> {
+    pub fn edges(&self, target: Target) -> Option<Edges<'_, A>> {
         let range = self.valid_edges_range(target)?;
         Some(Edges {
             alphabet: self.alphabet.as_slice(),
@@ -113,7 +121,7 @@ impl<A: Alphabet> Deterministic<A> {
     ///
     /// Gives an empty iterator when the node is invalid or has no edges. Use `edges` to find out
     /// which of the two possibilites it is.
-    pub fn iter_edges(&self, node: Target) -> EdgesIter<A> {
+    pub fn iter_edges(&self, node: Target) -> EdgesIter<'_, A> {
         let range = self.valid_edges_range(node)
             .unwrap_or(0..0);
         let edges = Edges {
@@ -124,7 +132,7 @@ impl<A: Alphabet> Deterministic<A> {
     }
 
     /// Get a mutable reference to the outgoing edges of a node.
-    pub fn edges_mut(&mut self, target: Target) -> Option<EdgesMut<A>> {
+    pub fn edges_mut(&mut self, target: Target) -> Option<EdgesMut<'_, A>> {
         let range = self.valid_edges_range(target)?;
         Some(EdgesMut {
             alphabet: self.alphabet.as_slice(),
@@ -136,7 +144,7 @@ impl<A: Alphabet> Deterministic<A> {
     ///
     /// Gives an empty iterator when the node is invalid or has no edges. Use `edges` to find out
     /// which of the two possibilites it is.
-    pub fn iter_edges_mut(&mut self, node: Target) -> EdgesIterMut<A> {
+    pub fn iter_edges_mut(&mut self, node: Target) -> EdgesIterMut<'_, A> {
         let range = self.valid_edges_range(node)
             .unwrap_or(0..0);
         let edges = EdgesMut {
@@ -169,7 +177,7 @@ impl<A: Alphabet> Deterministic<A> {
     }
 
     #[allow(unused)]
-    pub fn write_to(&self, output: &mut Write) -> io::Result<()>
+    pub fn write_to(&self, output: &mut dyn Write) -> io::Result<()>
         where for<'a> &'a A: Display
     {
         let mut writer = GraphWriter::new(output, Family::Directed, None)?;
@@ -191,7 +199,7 @@ impl<A: Alphabet> Deterministic<A> {
 }
 
 impl Target {
-    pub const ZERO: Target = Target(unsafe { NonZeroUsize::new_unchecked(1) });
+    pub const ZERO: Target = Target(NonZeroUsize::new(1).unwrap());
 
     /// Create the target representation.
     pub fn new(index: usize) -> Option<Self> {
@@ -210,7 +218,6 @@ impl Target {
 }
 
 impl<A: Alphabet> Edges<'_, A> {
-    #[allow(unused)]
     pub fn target(&self, ch: A) -> Result<Option<Target>, ()> {
         self.alphabet.binary_search(&ch).map_err(|_| ())
             .map(|idx| self.targets[idx])
@@ -1,19 +1,32 @@
-use std::collections::{HashMap, HashSet};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::collections::hash_map::Entry;
 use std::fmt::{Display, Debug};
 use std::io::{self, Write};
+use std::ops::{BitAnd, BitOr, BitXor, Not, Sub};
 
-use crate::{Alphabet, Ensure};
+use crate::{Alphabet, Ensure, Error};
 use crate::nondeterministic::NonDeterministic;
 use crate::deterministic::{Deterministic, Target};
 use crate::dot::{Family, Edge as DotEdge, GraphWriter, Node as DotNode};
 use crate::nfa::{self, Nfa};
+use crate::partition::Partition;
 use crate::regex::Regex;
+use crate::union_find::UnionFind;
+
+mod matcher;
+
+pub use self::matcher::Matcher;
 
 /// A node handle.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
 pub struct Node(pub usize);
 
+/// A deterministic finite automaton.
+///
+/// Equality compares the structure of two automata, not their languages. It coincides with
+/// language equality only between minimized automata.
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Dfa<A: Alphabet> {
     /// The deterministic graph, also stores the alphabet.
     graph: Deterministic<A>,
@@ -28,79 +41,175 @@ impl<A: Alphabet> Dfa<A> {
     /// States are numbered in an arbitrary order, except the start label 0. The automaton will
     /// deduce the used alphabet subset automatically and test whether it has been used
     /// consistently.
+    ///
+    /// # Panics
+    /// When the edges do not form a complete deterministic automaton, see `try_from_edges`.
     pub fn from_edges<I, V>(edge_iter: I, finals: V) -> Dfa<A>
     where 
         I: IntoIterator<Item=(usize, A, usize)>,
         V: IntoIterator<Item=usize>, 
         A: Clone + Debug,
     {
-        let mut edges = vec![Vec::new()];
-        let mut check = vec![HashSet::new()];
-        let mut states = HashSet::new();
-        states.insert(0);
+        Self::try_from_edges(edge_iter, finals)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Build a dfa from the connecting edges and final states, if they are consistent.
+    ///
+    /// The alphabet consists of the symbols on the edges of the start state 0. Every state must
+    /// have exactly one edge for each of these symbols and no others, this includes states which
+    /// only appear as a target or final state.
+    pub fn try_from_edges<I, V>(edge_iter: I, finals: V) -> Result<Dfa<A>, Error<A>>
+    where 
+        I: IntoIterator<Item=(usize, A, usize)>,
+        V: IntoIterator<Item=usize>, 
+    {
+        Self::build(edge_iter, finals, false)
+    }
+
+    /// Build a partial dfa from the connecting edges and final states.
+    ///
+    /// The alphabet consists of the symbols of all edges. States need not have an edge for each
+    /// symbol, a missing edge rejects all words which would follow it. This is as if it led into
+    /// an implicit non-final sink state, which `complete` makes explicit.
+    ///
+    /// # Panics
+    /// When a state has more than one edge for the same symbol, see `try_from_partial_edges`.
+    pub fn from_partial_edges<I, V>(edge_iter: I, finals: V) -> Dfa<A>
+    where 
+        I: IntoIterator<Item=(usize, A, usize)>,
+        V: IntoIterator<Item=usize>, 
+    {
+        Self::try_from_partial_edges(edge_iter, finals)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Build a partial dfa from the connecting edges and final states, if they are deterministic.
+    pub fn try_from_partial_edges<I, V>(edge_iter: I, finals: V) -> Result<Dfa<A>, Error<A>>
+    where 
+        I: IntoIterator<Item=(usize, A, usize)>,
+        V: IntoIterator<Item=usize>, 
+    {
+        Self::build(edge_iter, finals, true)
+    }
+
+    fn build<I, V>(edge_iter: I, finals: V, partial: bool) -> Result<Dfa<A>, Error<A>>
+    where 
+        I: IntoIterator<Item=(usize, A, usize)>,
+        V: IntoIterator<Item=usize>, 
+    {
+        // Indices are arbitrary input, so allocating for them must fail gracefully.
+        fn ensure_state<A: Alphabet>(edges: &mut Vec<BTreeMap<A, usize>>, state: usize)
+            -> Result<(), Error<A>>
+        {
+            let len = state.checked_add(1).ok_or(Error::StateOutOfRange(state))?;
+            edges.try_reserve(len.saturating_sub(edges.len()))
+                .map_err(|_| Error::StateOutOfRange(state))?;
+            edges.ensure_default(len);
+            Ok(())
+        }
+
+        let mut edges = vec![BTreeMap::new()];
 
         for (from, a, to) in edge_iter {
-            edges.ensure_default(from + 1);
-            edges.ensure_default(to + 1);
-            check.ensure_default(from + 1);
-            check.ensure_default(to + 1);
-            
-            edges[from].push((a, to));
-            check[from].insert(a);
-            states.insert(from);
-            states.insert(to);
+            ensure_state(&mut edges, from.max(to))?;
+
+            if edges[from].insert(a, to).is_some() {
+                return Err(Error::DuplicateTransition { state: from, symbol: a });
+            }
         }
 
         let finals = finals.into_iter()
-            .inspect(|c| check.resize(c + 1, HashSet::new()))
-            .map(Target::make)
-            .collect();
+            .map(|fin| {
+                ensure_state(&mut edges, fin)?;
+                Ok(Target::make(fin))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let alphabet = if partial {
+            edges.iter()
+                .flat_map(BTreeMap::keys)
+                .cloned()
+                .collect::<BTreeSet<_>>()
+        } else {
+            edges[0].keys().cloned().collect()
+        };
+
+        for (state, edge_list) in edges.iter().enumerate().filter(|_| !partial) {
+            if let Some(&symbol) = edge_list.keys().find(|symbol| !alphabet.contains(symbol)) {
+                return Err(Error::AlphabetMismatch { state, symbol });
+            }
 
-        let alphabet = check.pop();
-        if let Some(sample) = alphabet.as_ref() {
-            if let Some(err) = check.iter().find(|&s| s != sample) {
-                panic!("Different outgoing edges alphabet: {:?} vs {:?}", &sample, &err);
+            if let Some(&symbol) = alphabet.iter().find(|symbol| !edge_list.contains_key(symbol)) {
+                return Err(Error::MissingTransition { state, symbol });
             }
         }
 
-        let mut graph = Deterministic::new(alphabet.unwrap());
+        let mut graph = Deterministic::new(alphabet);
 
-        for edge_list in edges.iter_mut() {
-            // There are never any duplicates and now the indices correspond to
-            // the indices in the alphabet list.
-            edge_list.sort_unstable();
-            let node = graph.node();
-            let edges = graph.iter_edges_mut(node);
-            let edge_list = edge_list.iter().cloned();
+        for edge_list in edges.iter() {
+            let node = graph.try_node()?;
+            let mut edges = graph.edges_mut(node).unwrap();
 
-            for ((_, target), (_, edge_target)) in edges.zip(edge_list) {
-                *target = Some(Target::make(edge_target));
+            for (&symbol, &edge_target) in edge_list.iter() {
+                edges[symbol] = Some(Target::make(edge_target));
             }
         }
 
-        assert!(graph.is_complete());
+        debug_assert!(partial || graph.is_complete());
 
-        Dfa {
+        Ok(Dfa {
             graph,
             finals,
-        }
+        })
     }
 
     /// Checks if the input word is contained in the language.
+    ///
+    /// Words leading to a missing edge of a partial automaton are rejected, as are words with
+    /// symbols outside the alphabet.
     pub fn contains<I: IntoIterator<Item=A>>(&self, sequence: I) -> bool {
         let mut state = Target::ZERO;
 
         for ch in sequence {
-            let next = self.graph
-                .edges(state).unwrap()
-                [ch].unwrap();
-            state = next;
+            match self.graph.edges(state).unwrap().target(ch) {
+                Ok(Some(next)) => state = next,
+                // A missing edge leads into the implicit sink, unknown symbols are never accepted.
+                Ok(None) | Err(()) => return false,
+            }
         }
 
         self.finals.contains(&state)
     }
 
-    pub fn write_to(&self, output: &mut Write) -> io::Result<()> 
+    /// Checks if the input word is contained in the language, requiring it to be a word over the
+    /// alphabet.
+    ///
+    /// Returns an error for the first symbol which is not part of the alphabet, even if the word
+    /// had already been rejected by a missing edge before it.
+    pub fn try_contains<I: IntoIterator<Item=A>>(&self, sequence: I) -> Result<bool, Error<A>> {
+        let mut state = Some(Target::ZERO);
+
+        for (position, ch) in sequence.into_iter().enumerate() {
+            let unknown = || Error::UnknownSymbol { position, symbol: ch };
+            state = match state {
+                Some(current) => self.graph.edges(current).unwrap()
+                    .target(ch)
+                    .map_err(|()| unknown())?,
+                None if self.alphabet().binary_search(&ch).is_ok() => None,
+                None => return Err(unknown()),
+            };
+        }
+
+        Ok(state.is_some_and(|state| self.finals.contains(&state)))
+    }
+
+    /// A cursor to match words incrementally, starting in the start state.
+    pub fn matcher(&self) -> Matcher<'_, A> {
+        Matcher::new(self)
+    }
+
+    pub fn write_to(&self, output: &mut dyn Write) -> io::Result<()> 
         where for<'a> &'a A: Display
     {
         let mut writer = GraphWriter::new(output, Family::Directed, None)?;
@@ -132,27 +241,144 @@ impl<A: Alphabet> Dfa<A> {
         self.graph.alphabet()
     }
 
-    /// Minimize the automata into its language partition.
+    /// An automaton for the same language over a larger alphabet.
     ///
-    /// NOT YET IMPLEMENTED!
+    /// All edges with the new symbols lead into a new, non-final sink state. Missing edges of a
+    /// partial automaton lead into the same sink, so that the result is complete. When all symbols
+    /// are already part of the alphabet, this is a plain copy.
+    pub fn extend_alphabet<I: IntoIterator<Item=A>>(&self, symbols: I) -> Self {
+        let alphabet = self.alphabet().iter().cloned()
+            .chain(symbols)
+            .collect::<Vec<_>>();
+        let mut graph = Deterministic::new(alphabet);
+        if graph.alphabet() == self.alphabet() {
+            return self.clone();
+        }
+
+        for _ in 0..self.graph.node_count() {
+            graph.node();
+        }
+        let sink = graph.node();
+
+        for state in (0..self.graph.node_count()).map(Target::make) {
+            let old_edges = self.graph.edges(state).unwrap();
+            for (symbol, target) in graph.iter_edges_mut(state) {
+                *target = match self.graph.alphabet().binary_search(symbol) {
+                    Ok(_) => old_edges[*symbol].or(Some(sink)),
+                    Err(_) => Some(sink),
+                };
+            }
+        }
+
+        for (_, target) in graph.iter_edges_mut(sink) {
+            *target = Some(sink);
+        }
+
+        Dfa {
+            graph,
+            finals: self.finals.clone(),
+        }
+    }
+
+    /// Minimize the automata into its language partition.
     ///
     /// Contrary to NFAs, the resulting automaton is guaranteed to be a minimal
     /// automaton exactly equivalent to the languages minimal DFA.
+    ///
+    /// Unreachable states are dropped and language equivalent states are merged by Hopcroft's
+    /// partition refinement, in `O(n·|Σ|·log n)`. States of the result are numbered in breadth
+    /// first order from the start state 0, following edges in the order of the alphabet. Hence,
+    /// two minimized automata of the same language over the same alphabet are identical. Missing
+    /// edges of a partial automaton lead into a sink, which is part of the result when reachable.
     pub fn minimized(&self) -> Self {
-        unimplemented!()
+        let automaton = self.completed();
+        let count = automaton.graph.node_count();
+        let char_count = automaton.graph.char_count();
+
+        // Incoming edges of each node, grouped by the symbol index.
+        let mut incoming = vec![Vec::new(); count*char_count];
+        for from in automaton.graph.iter() {
+            for (symbol, to) in automaton.graph[from].iter().enumerate() {
+                let to = to.expect("Completed automaton has all edges");
+                incoming[to.index()*char_count + symbol].push(from.index());
+            }
+        }
+
+        let mut partition = Partition::new(count);
+        automaton.finals.iter().for_each(|fin| partition.mark(fin.index()));
+
+        // The blocks whose predecessors have not yet been used to split other blocks.
+        let mut waiting = Vec::new();
+        let mut is_waiting = Vec::new();
+        let mut splits = Vec::new();
+
+        partition.split(|old, new| splits.push((old, new)));
+        schedule_splits(&partition, &mut splits, &mut waiting, &mut is_waiting);
+
+        while let Some(splitter) = waiting.pop() {
+            is_waiting[splitter] = false;
+            let members = partition.block(splitter).to_vec();
+
+            for symbol in 0..char_count {
+                for &to in members.iter() {
+                    for &from in incoming[to*char_count + symbol].iter() {
+                        partition.mark(from);
+                    }
+                }
+                partition.split(|old, new| splits.push((old, new)));
+                schedule_splits(&partition, &mut splits, &mut waiting, &mut is_waiting);
+            }
+        }
+
+        let classes = (0..count)
+            .map(|state| partition.block_of(state))
+            .collect::<Vec<_>>();
+        automaton.quotient(&classes, partition.len())
+    }
+
+    /// Minimize the automaton with Brzozowski's algorithm.
+    ///
+    /// Reverses the automaton and determinizes it, twice. Determinizing the reversal of a
+    /// reachable dfa yields a minimal automaton of the reversed language, so the second round
+    /// results in the minimal automaton. While the worst case is exponential, it is often fast for
+    /// automata obtained by the powerset construction. The result is numbered in the same canonical
+    /// order as in `minimized`, so both results are identical.
+    pub fn minimized_brzozowski(&self) -> Self {
+        let alphabet = self.alphabet().to_vec();
+        let reversed = self.reverse()
+            .into_dfa(alphabet.iter().cloned());
+        let minimal = reversed.reverse()
+            .into_dfa(alphabet.iter().cloned());
+
+        let classes = (0..minimal.graph.node_count()).collect::<Vec<_>>();
+        minimal.quotient(&classes, classes.len())
     }
 
     /// Pairs two automata with a given binary boolean operation
     ///
-    /// If there are no final states, returns `None`.
+    /// If there are no final states, returns `None`. Use `product` for an automaton which
+    /// represents the empty language explicitly.
     pub fn pair<F>(&self, rhs: &Self, decider: F) -> Option<Self>
         where F: Fn(bool, bool) -> bool
     {
-        assert!(self.alphabet() == rhs.alphabet(), "Automata alphabets differ");
+        Some(self.product(rhs, decider))
+            .filter(|product| !product.finals.is_empty())
+    }
+
+    /// Pairs two automata with a given binary boolean operation.
+    ///
+    /// The result accepts a word if `decider` accepts the membership of the word in both automata.
+    /// Only pairs of states reachable from the start are constructed. When the alphabets of the
+    /// automata differ, both are first lifted to the union of the alphabets with
+    /// `extend_alphabet`, and so is the result.
+    pub fn product<F>(&self, rhs: &Self, decider: F) -> Self
+        where F: Fn(bool, bool) -> bool
+    {
+        let (lhs, rhs) = self.unified(rhs);
 
         let mut assigned = HashMap::new();
         let mut working = vec![(Target::ZERO, Target::ZERO, Target::ZERO)];
-        let mut graph = Deterministic::new(self.alphabet().iter().cloned());
+        let mut graph = Deterministic::new(lhs.alphabet().iter().cloned());
         let mut finals = HashSet::new();
 
         assigned.insert((Target::ZERO, Target::ZERO), Target::ZERO);
@@ -160,14 +386,14 @@ impl<A: Alphabet> Dfa<A> {
 
         while let Some((left, right, self_id)) = working.pop() {
             let decide = decider(
-                self.finals.contains(&left),
+                lhs.finals.contains(&left),
                 rhs.finals.contains(&right));
 
             if decide {
                 finals.insert(self_id);
             }
 
-            let left_edges = self.graph.iter_edges(left);
+            let left_edges = lhs.graph.iter_edges(left);
             let right_edges = rhs.graph.iter_edges(right);
 
             for ((symbol, new_left), (_, new_right)) in left_edges.zip(right_edges) {
@@ -186,16 +412,53 @@ impl<A: Alphabet> Dfa<A> {
             }
         }
 
-        if finals.is_empty() {
-            None
-        } else {
-            Some(Dfa {
-                graph,
-                finals
-            })
+        Dfa {
+            graph,
+            finals,
         }
     }
 
+    /// An automaton accepting the words of either automaton.
+    pub fn union(&self, rhs: &Self) -> Self {
+        self.product(rhs, |lhs, rhs| lhs || rhs)
+    }
+
+    /// An automaton accepting the words of both automata.
+    pub fn intersection(&self, rhs: &Self) -> Self {
+        self.product(rhs, |lhs, rhs| lhs && rhs)
+    }
+
+    /// An automaton accepting the words of this automaton which `rhs` rejects.
+    pub fn difference(&self, rhs: &Self) -> Self {
+        self.product(rhs, |lhs, rhs| lhs && !rhs)
+    }
+
+    /// An automaton accepting the words of exactly one of the automata.
+    pub fn symmetric_difference(&self, rhs: &Self) -> Self {
+        self.product(rhs, |lhs, rhs| lhs != rhs)
+    }
+
+    /// An automaton accepting exactly the words rejected by this one.
+    ///
+    /// The complement is relative to all words over the alphabet of the automaton. A partial
+    /// automaton is completed first.
+    pub fn complement(&self) -> Self {
+        !self.clone()
+    }
+
+    /// An equivalent complete automaton.
+    ///
+    /// Each missing edge of a partial automaton is replaced by an edge into a new, non-final sink
+    /// state. A complete automaton is returned unchanged.
+    pub fn complete(&self) -> Self {
+        self.clone().into_complete()
+    }
+
+    /// Check whether every state has an edge for every symbol.
+    pub fn is_complete(&self) -> bool {
+        self.graph.is_complete()
+    }
+
     /// Like `pair` but only determines if the result would be an empty automaton.
     ///
     /// This speeds up operations such as equivalence checks. Equivalent to
@@ -207,10 +470,13 @@ impl<A: Alphabet> Dfa<A> {
     /// decider function. A DFA is universal iff all of its reachable states are 
     /// final, which is the same as checking that in the complement all reachable
     /// states are non-final.
+    ///
+    /// Automata over different alphabets are lifted to the union of the alphabets, as in
+    /// `product`.
     pub fn pair_empty<F>(&self, rhs: &Self, decider: F) -> bool
         where F: Fn(bool, bool) -> bool
     {
-        assert!(self.alphabet() == rhs.alphabet(), "Automata alphabets differ");
+        let (lhs, rhs) = self.unified(rhs);
 
         let mut assigned = HashSet::new();
         let mut working = vec![(Target::ZERO, Target::ZERO)];
@@ -218,14 +484,14 @@ impl<A: Alphabet> Dfa<A> {
 
         while let Some((left, right)) = working.pop() {
             let decide = decider(
-                self.finals.contains(&left),
+                lhs.finals.contains(&left),
                 rhs.finals.contains(&right));
 
             if decide {
                 return false;
             }
 
-            let left_edges = self.graph.iter_edges(left);
+            let left_edges = lhs.graph.iter_edges(left);
             let right_edges = rhs.graph.iter_edges(right);
 
             for ((_, new_left), (_, new_right)) in left_edges.zip(right_edges) {
@@ -238,6 +504,334 @@ impl<A: Alphabet> Dfa<A> {
         true
     }
 
+    /// Check whether both automata accept the same language.
+    ///
+    /// On failure, returns a shortest word accepted by exactly one of the automata.
+    pub fn equivalent(&self, rhs: &Self) -> Result<(), Vec<A>> {
+        self.shortest_witness(rhs, |lhs, rhs| lhs != rhs)
+            .map_or(Ok(()), Err)
+    }
+
+    /// Check whether every word accepted by this automaton is also accepted by `rhs`.
+    ///
+    /// On failure, returns a shortest word accepted by this automaton but not by `rhs`.
+    pub fn is_subset_of(&self, rhs: &Self) -> Result<(), Vec<A>> {
+        self.shortest_witness(rhs, |lhs, rhs| lhs && !rhs)
+            .map_or(Ok(()), Err)
+    }
+
+    /// Check whether both automata accept the same language, with the algorithm of Hopcroft and
+    /// Karp.
+    ///
+    /// Instead of exploring all reachable pairs of states as `equivalent` does, states assumed to
+    /// be equivalent are merged in a union-find structure and each merge is checked only once.
+    /// This takes nearly linear time in the number of states of both automata.
+    pub fn equivalent_hopcroft_karp(&self, rhs: &Self) -> bool {
+        self.hopcroft_karp(rhs, false).is_ok()
+    }
+
+    /// Like `equivalent_hopcroft_karp` but returns a word accepted by exactly one automaton.
+    ///
+    /// The word is not necessarily a shortest one, as pairs of already merged states are skipped.
+    /// Recording the words of all checked pairs has a small cost in time and memory.
+    pub fn equivalent_hopcroft_karp_witness(&self, rhs: &Self) -> Result<(), Vec<A>> {
+        self.hopcroft_karp(rhs, true)
+            .map_err(|word| word.unwrap())
+    }
+
+    /// States of `rhs` are numbered after those of `self` in the union-find structure.
+    fn hopcroft_karp(&self, rhs: &Self, witness: bool) -> Result<(), Option<Vec<A>>> {
+        let (lhs, rhs) = self.unified(rhs);
+
+        let offset = lhs.graph.node_count();
+        let mut sets = UnionFind::new(offset + rhs.graph.node_count());
+        // The pair and symbol from which each checked pair was reached.
+        let mut trace = Vec::new();
+        let mut working = VecDeque::new();
+
+        sets.union(Target::ZERO.index(), offset + Target::ZERO.index());
+        working.push_back((Target::ZERO, Target::ZERO, 0));
+        if witness {
+            trace.push(None);
+        }
+
+        while let Some((left, right, index)) = working.pop_front() {
+            if lhs.finals.contains(&left) != rhs.finals.contains(&right) {
+                if !witness {
+                    return Err(None);
+                }
+
+                let mut word = Vec::new();
+                let mut current = index;
+                while let Some((previous, symbol)) = trace[current] {
+                    word.push(symbol);
+                    current = previous;
+                }
+                word.reverse();
+                return Err(Some(word));
+            }
+
+            let left_edges = lhs.graph.iter_edges(left);
+            let right_edges = rhs.graph.iter_edges(right);
+
+            for ((symbol, new_left), (_, new_right)) in left_edges.zip(right_edges) {
+                if sets.union(new_left.index(), offset + new_right.index()) {
+                    if witness {
+                        trace.push(Some((index, *symbol)));
+                    }
+                    working.push_back((new_left, new_right, trace.len().saturating_sub(1)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find a shortest word which would be accepted by the product with `decider`.
+    ///
+    /// Explores pairs of states in breadth first order, remembering the pair and symbol from
+    /// which each one was first reached. The word is then read backwards along those edges.
+    fn shortest_witness<F>(&self, rhs: &Self, decider: F) -> Option<Vec<A>>
+        where F: Fn(bool, bool) -> bool
+    {
+        let (lhs, rhs) = self.unified(rhs);
+
+        let start = (Target::ZERO, Target::ZERO);
+        let mut predecessor = HashMap::new();
+        let mut working = VecDeque::new();
+        predecessor.insert(start, None);
+        working.push_back(start);
+
+        while let Some((left, right)) = working.pop_front() {
+            let decide = decider(
+                lhs.finals.contains(&left),
+                rhs.finals.contains(&right));
+
+            if decide {
+                let mut word = Vec::new();
+                let mut current = (left, right);
+                while let Some((previous, symbol)) = predecessor[&current] {
+                    word.push(symbol);
+                    current = previous;
+                }
+                word.reverse();
+                return Some(word);
+            }
+
+            let left_edges = lhs.graph.iter_edges(left);
+            let right_edges = rhs.graph.iter_edges(right);
+
+            for ((symbol, new_left), (_, new_right)) in left_edges.zip(right_edges) {
+                if let Entry::Vacant(vacant) = predecessor.entry((new_left, new_right)) {
+                    vacant.insert(Some(((left, right), *symbol)));
+                    working.push_back((new_left, new_right));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Lift both automata to the union of their alphabets and complete them.
+    ///
+    /// Borrows each automaton which is already complete over the common alphabet.
+    fn unified<'a>(&'a self, rhs: &'a Self) -> (Cow<'a, Self>, Cow<'a, Self>) {
+        fn lift<'a, A: Alphabet>(dfa: &'a Dfa<A>, other: &Dfa<A>) -> Cow<'a, Dfa<A>> {
+            if dfa.alphabet() == other.alphabet() {
+                dfa.completed()
+            } else {
+                // Only a plain copy when no symbol is new, which may still be partial.
+                let extended = dfa.extend_alphabet(other.alphabet().iter().cloned());
+                Cow::Owned(extended.into_complete())
+            }
+        }
+
+        (lift(self, rhs), lift(rhs, self))
+    }
+
+    /// Borrow the automaton if it is complete, otherwise complete a copy.
+    fn completed(&self) -> Cow<'_, Self> {
+        if self.graph.is_complete() {
+            Cow::Borrowed(self)
+        } else {
+            Cow::Owned(self.clone().into_complete())
+        }
+    }
+
+    fn into_complete(mut self) -> Self {
+        if self.graph.is_complete() {
+            return self;
+        }
+
+        let sink = self.graph.node();
+        for state in self.graph.iter() {
+            for (_, target) in self.graph.iter_edges_mut(state) {
+                target.get_or_insert(sink);
+            }
+        }
+
+        self
+    }
+
+    /// Build the automaton on classes of states, reachable from the start state.
+    ///
+    /// All states in a class must have the same finality and their edges must lead into the same
+    /// classes. The states of the result are numbered by a breadth first search from the start,
+    /// following edges in the order of the alphabet.
+    fn quotient(&self, classes: &[usize], class_count: usize) -> Self {
+        let mut graph = Deterministic::new(self.alphabet().iter().cloned());
+        let mut finals = HashSet::new();
+        let mut assigned = vec![None; class_count];
+        let mut working = VecDeque::new();
+
+        assigned[classes[Target::ZERO.index()]] = Some(graph.node());
+        working.push_back(Target::ZERO);
+
+        while let Some(state) = working.pop_front() {
+            let from = assigned[classes[state.index()]].unwrap();
+            if self.finals.contains(&state) {
+                finals.insert(from);
+            }
+
+            let targets = self.graph[state].iter()
+                .map(|&to| to.map(|to| match assigned[classes[to.index()]] {
+                    Some(id) => id,
+                    None => {
+                        let new_id = graph.node();
+                        assigned[classes[to.index()]] = Some(new_id);
+                        working.push_back(to);
+                        new_id
+                    },
+                }))
+                .collect::<Vec<_>>();
+
+            let edges = graph.iter_edges_mut(from);
+            for ((_, target), new_target) in edges.zip(targets) {
+                *target = new_target;
+            }
+        }
+
+        Dfa {
+            graph,
+            finals,
+        }
+    }
+
+    /// Remove the states which are unreachable or from which no final state is reachable.
+    ///
+    /// The remaining states keep their relative order, the start state stays 0. In a complete
+    /// automaton, all reachable states without a path to a final state are merged into a single
+    /// sink, such that the result is complete as well. In a partial automaton they are removed,
+    /// as are the edges into them.
+    ///
+    /// Also returns the new index of each previous state, if it was kept.
+    pub fn trim(&self) -> (Self, Vec<Option<usize>>) {
+        let count = self.graph.node_count();
+        let live = self.live_states();
+        let complete = self.graph.is_complete();
+
+        let mut reachable = vec![false; count];
+        let mut working = vec![Target::ZERO];
+        reachable[Target::ZERO.index()] = true;
+        while let Some(state) = working.pop() {
+            for (_, to) in self.graph.iter_edges(state) {
+                if !reachable[to.index()] {
+                    reachable[to.index()] = true;
+                    working.push(to);
+                }
+            }
+        }
+
+        let mut renumbering = vec![None; count];
+        let mut next = 0;
+        let mut sink = None;
+        for state in 0..count {
+            if !reachable[state] {
+                continue;
+            }
+
+            if !live[state] {
+                // The start state is kept even if the language is empty.
+                if !complete && state != Target::ZERO.index() {
+                    continue;
+                }
+
+                if let Some(sink) = sink {
+                    renumbering[state] = Some(sink);
+                    continue;
+                }
+
+                sink = Some(next);
+            }
+
+            renumbering[state] = Some(next);
+            next += 1;
+        }
+
+        let mut graph = Deterministic::new(self.alphabet().iter().cloned());
+        for _ in 0..next {
+            graph.node();
+        }
+
+        for from in self.graph.iter() {
+            let new_from = match renumbering[from.index()] {
+                Some(new_from) => Target::make(new_from),
+                None => continue,
+            };
+
+            let targets = self.graph[from].iter()
+                .map(|to| to.and_then(|to| renumbering[to.index()]).map(Target::make))
+                .collect::<Vec<_>>();
+            for ((_, target), new_target) in graph.iter_edges_mut(new_from).zip(targets) {
+                *target = new_target;
+            }
+        }
+
+        let finals = self.finals.iter()
+            .filter_map(|fin| renumbering[fin.index()])
+            .map(Target::make)
+            .collect();
+
+        (Dfa { graph, finals }, renumbering)
+    }
+
+    /// Whether a final state is reachable from each state.
+    fn live_states(&self) -> Vec<bool> {
+        let count = self.graph.node_count();
+        let mut incoming = vec![Vec::new(); count];
+        for from in self.graph.iter() {
+            for (_, to) in self.graph.iter_edges(from) {
+                incoming[to.index()].push(from.index());
+            }
+        }
+
+        let mut live = vec![false; count];
+        let mut working = self.finals.iter()
+            .map(|fin| fin.index())
+            .collect::<Vec<_>>();
+        working.iter().for_each(|&fin| live[fin] = true);
+
+        while let Some(to) = working.pop() {
+            for &from in incoming[to].iter() {
+                if !live[from] {
+                    live[from] = true;
+                    working.push(from);
+                }
+            }
+        }
+
+        live
+    }
+
+    /// An nfa for the reversed language.
+    ///
+    /// The reversal of a deterministic automaton is usually not deterministic. As in
+    /// `Nfa::reverse`, the new start state 0 has epsilon transitions to all previously final
+    /// states, now shifted by one, and the previous start state 1 is the only final state.
+    pub fn reverse(&self) -> Nfa<A> {
+        self.to_nfa().reverse()
+    }
+
     /// Get an equivalent nfa.
     pub fn to_nfa(&self) -> Nfa<A> {
         let graph = NonDeterministic::from_deterministic_with(&self.graph, Some);
@@ -265,6 +859,76 @@ impl<A: Alphabet> Dfa<A> {
     }
 }
 
+/// Add blocks resulting from splits to the waiting splitters of Hopcroft's algorithm.
+///
+/// When the old block was still waiting, both parts must be used as splitters. Otherwise, one of
+/// them suffices since the old block has already been used and splitting by one part and the
+/// union is the same as splitting by the other part. Choosing the smaller one gives the
+/// logarithmic factor in the running time.
+fn schedule_splits(
+    partition: &Partition,
+    splits: &mut Vec<(usize, usize)>,
+    waiting: &mut Vec<usize>,
+    is_waiting: &mut Vec<bool>,
+) {
+    is_waiting.resize(partition.len(), false);
+    for (old, new) in splits.drain(..) {
+        let add = if is_waiting[old] || partition.block(new).len() <= partition.block(old).len() {
+            new
+        } else {
+            old
+        };
+        is_waiting[add] = true;
+        waiting.push(add);
+    }
+}
+
+macro_rules! binary_op {
+    ($trait:ident, $method:ident, $operation:ident) => {
+        impl<A: Alphabet> $trait<&'_ Dfa<A>> for &'_ Dfa<A> {
+            type Output = Dfa<A>;
+
+            fn $method(self, rhs: &Dfa<A>) -> Dfa<A> {
+                self.$operation(rhs)
+            }
+        }
+
+        impl<A: Alphabet> $trait for Dfa<A> {
+            type Output = Dfa<A>;
+
+            fn $method(self, rhs: Dfa<A>) -> Dfa<A> {
+                self.$operation(&rhs)
+            }
+        }
+    };
+}
+
+binary_op!(BitOr, bitor, union);
+binary_op!(BitAnd, bitand, intersection);
+binary_op!(BitXor, bitxor, symmetric_difference);
+binary_op!(Sub, sub, difference);
+
+impl<A: Alphabet> Not for &'_ Dfa<A> {
+    type Output = Dfa<A>;
+
+    fn not(self) -> Dfa<A> {
+        self.complement()
+    }
+}
+
+impl<A: Alphabet> Not for Dfa<A> {
+    type Output = Dfa<A>;
+
+    fn not(self) -> Dfa<A> {
+        let mut this = self.into_complete();
+        let finals = this.graph.iter()
+            .filter(|state| !this.finals.contains(state))
+            .collect();
+        this.finals = finals;
+        this
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,7 +994,7 @@ mod tests {
             (2, '.', 0),
         ], vec![0]);
 
-        let accept_6_0 = automaton_2.pair(&automaton_3, &|lhs, rhs| lhs & rhs).unwrap();
+        let accept_6_0 = automaton_2.pair(&automaton_3, |lhs, rhs| lhs & rhs).unwrap();
         assert!( accept_6_0.contains("".chars()));
         assert!(!accept_6_0.contains(".".chars()));
         assert!(!accept_6_0.contains("..".chars()));
@@ -339,7 +1003,7 @@ mod tests {
         assert!(!accept_6_0.contains(".....".chars()));
         assert!( accept_6_0.contains("......".chars()));
 
-        let accept_6_1 = automaton_2.pair(&automaton_3, &|lhs, rhs| lhs | rhs).unwrap();
+        let accept_6_1 = automaton_2.pair(&automaton_3, |lhs, rhs| lhs | rhs).unwrap();
         assert!( accept_6_1.contains("".chars()));
         assert!(!accept_6_1.contains(".".chars()));
         assert!( accept_6_1.contains("..".chars()));
@@ -376,4 +1040,486 @@ mod tests {
         assert!( automaton_even.pair_empty(&automaton_odd, |lhs, rhs| lhs & rhs));
         assert!( automaton_even.pair_empty(&automaton_odd, |lhs, rhs| !(lhs | rhs)));
     }
+
+    #[test]
+    fn boolean_operations() {
+        // Accepts words with `len(w) % 2 == 0`
+        let automaton_2 = Dfa::from_edges(vec![
+            (0, '.', 1),
+            (1, '.', 0),
+        ], vec![0]);
+
+        // Accepts words with `len(w) % 3 == 0`
+        let automaton_3 = Dfa::from_edges(vec![
+            (0, '.', 1),
+            (1, '.', 2),
+            (2, '.', 0),
+        ], vec![0]);
+
+        let word = |len| std::iter::repeat_n('.', len);
+        let union = &automaton_2 | &automaton_3;
+        let intersection = &automaton_2 & &automaton_3;
+        let difference = &automaton_2 - &automaton_3;
+        let symmetric = &automaton_2 ^ &automaton_3;
+        let complement = !&automaton_2;
+
+        for len in 0..12 {
+            let (even, third) = (len % 2 == 0, len % 3 == 0);
+            assert_eq!(union.contains(word(len)), even || third);
+            assert_eq!(intersection.contains(word(len)), even && third);
+            assert_eq!(difference.contains(word(len)), even && !third);
+            assert_eq!(symmetric.contains(word(len)), even != third);
+            assert_eq!(complement.contains(word(len)), !even);
+        }
+
+        assert_eq!(union, automaton_2.clone() | automaton_3.clone());
+        assert_eq!(!complement, automaton_2);
+    }
+
+    #[test]
+    fn empty_operations() {
+        let automaton = Dfa::from_edges(vec![
+            (0, '.', 1),
+            (1, '.', 0),
+        ], vec![0]);
+
+        let empty = automaton.difference(&automaton);
+        assert!(automaton.pair(&automaton, |lhs, rhs| lhs && !rhs).is_none());
+        assert!(!empty.contains(None));
+        assert!(!empty.contains(Some('.')));
+
+        let universal = !&empty;
+        assert!(universal.contains(None));
+        assert!(universal.contains(Some('.')));
+        assert_eq!(universal.minimized(), (&automaton | &!&automaton).minimized());
+    }
+
+    #[test]
+    fn equivalence() {
+        // Accepts words with an even number of `1`.
+        let automaton = Dfa::from_edges(vec![
+            (0, '0', 0),
+            (0, '1', 1),
+            (1, '0', 1),
+            (1, '1', 0),
+        ], vec![0]);
+
+        // The same language, with redundant states.
+        let redundant = Dfa::from_edges(vec![
+            (0, '0', 2),
+            (0, '1', 1),
+            (1, '0', 1),
+            (1, '1', 2),
+            (2, '0', 0),
+            (2, '1', 3),
+            (3, '0', 3),
+            (3, '1', 0),
+        ], vec![0, 2]);
+
+        // Accepts words with an even number of `1` ending in `0`, and the empty word.
+        let ending = Dfa::from_edges(vec![
+            (0, '0', 0),
+            (0, '1', 1),
+            (1, '0', 1),
+            (1, '1', 2),
+            (2, '0', 0),
+            (2, '1', 1),
+        ], vec![0]);
+
+        assert_eq!(automaton.equivalent(&redundant), Ok(()));
+        assert_eq!(redundant.equivalent(&automaton), Ok(()));
+        assert_eq!(automaton.equivalent(&ending), Err(vec!['1', '1']));
+
+        assert_eq!(ending.is_subset_of(&automaton), Ok(()));
+        assert_eq!(automaton.is_subset_of(&ending), Err(vec!['1', '1']));
+        assert_eq!(automaton.is_subset_of(&!&automaton), Err(vec![]));
+    }
+
+    #[test]
+    fn equivalence_hopcroft_karp() {
+        // Accepts words with an even number of `1`.
+        let automaton = Dfa::from_edges(vec![
+            (0, '0', 0),
+            (0, '1', 1),
+            (1, '0', 1),
+            (1, '1', 0),
+        ], vec![0]);
+
+        // The same language, with redundant states.
+        let redundant = Dfa::from_edges(vec![
+            (0, '0', 2),
+            (0, '1', 1),
+            (1, '0', 1),
+            (1, '1', 2),
+            (2, '0', 0),
+            (2, '1', 3),
+            (3, '0', 3),
+            (3, '1', 0),
+        ], vec![0, 2]);
+
+        // Accepts words with an odd number of `0`.
+        let odd = Dfa::from_edges(vec![
+            (0, '0', 1),
+            (0, '1', 0),
+            (1, '0', 0),
+            (1, '1', 1),
+        ], vec![1]);
+
+        assert!(automaton.equivalent_hopcroft_karp(&redundant));
+        assert!(redundant.equivalent_hopcroft_karp(&automaton));
+        assert_eq!(automaton.equivalent_hopcroft_karp_witness(&redundant), Ok(()));
+
+        assert!(!automaton.equivalent_hopcroft_karp(&odd));
+        let witness = automaton.equivalent_hopcroft_karp_witness(&odd).unwrap_err();
+        assert_ne!(automaton.contains(witness.iter().cloned()), odd.contains(witness.iter().cloned()));
+        assert_eq!(witness, vec![]);
+
+        let even = odd.complement();
+        let witness = automaton.equivalent_hopcroft_karp_witness(&even).unwrap_err();
+        assert_ne!(automaton.contains(witness.iter().cloned()), even.contains(witness.iter().cloned()));
+    }
+
+    #[test]
+    fn different_alphabets() {
+        // Accepts words over `a`, `b` with an even number of `a`.
+        let even = Dfa::from_edges(vec![
+            (0, 'a', 1),
+            (0, 'b', 0),
+            (1, 'a', 0),
+            (1, 'b', 1),
+        ], vec![0]);
+
+        // Accepts words over `a`, `c` ending in `c`.
+        let ending = Dfa::from_edges(vec![
+            (0, 'a', 0),
+            (0, 'c', 1),
+            (1, 'a', 0),
+            (1, 'c', 1),
+        ], vec![1]);
+
+        let extended = even.extend_alphabet(vec!['c', 'a']);
+        assert_eq!(extended.alphabet(), &['a', 'b', 'c']);
+        assert!( extended.contains("abba".chars()));
+        assert!(!extended.contains("abca".chars()));
+        assert_eq!(even.extend_alphabet(Some('b')), even);
+
+        // Missing edges share the sink of the new symbols.
+        let partial = Dfa::from_partial_edges(vec![(0, 'a', 1)], vec![1]);
+        let extended_partial = partial.extend_alphabet(Some('b'));
+        assert!(extended_partial.is_complete());
+        assert_eq!(extended_partial.graph.node_count(), 3);
+        assert_eq!((&partial | &ending).graph.node_count(), 4);
+
+        let union = even.union(&ending);
+        assert_eq!(union.alphabet(), &['a', 'b', 'c']);
+        assert!( union.contains("bb".chars()));
+        assert!( union.contains("ac".chars()));
+        assert!(!union.contains("acb".chars()));
+
+        // Words containing `c` are not in the language of `even`.
+        let difference = &ending - &even;
+        assert!( difference.contains("aac".chars()));
+        assert!(!difference.contains("bac".chars()));
+        assert!( even.pair(&ending, |lhs, rhs| lhs && rhs).is_none());
+        assert!( even.pair_empty(&ending, |lhs, rhs| lhs && rhs));
+        assert_eq!(even.equivalent(&ending), Err(vec![]));
+        assert_eq!(ending.is_subset_of(&difference), Ok(()));
+        assert!(even.equivalent_hopcroft_karp(&extended));
+    }
+
+    #[test]
+    fn invalid_edges() {
+        let missing = Dfa::try_from_edges(vec![
+            (0, '0', 1),
+            (0, '1', 0),
+            (1, '0', 1),
+        ], vec![1]);
+        assert_eq!(missing, Err(Error::MissingTransition { state: 1, symbol: '1' }));
+
+        let duplicate = Dfa::try_from_edges(vec![
+            (0, '0', 0),
+            (0, '0', 1),
+            (1, '0', 1),
+        ], vec![1]);
+        assert_eq!(duplicate, Err(Error::DuplicateTransition { state: 0, symbol: '0' }));
+
+        let mismatch = Dfa::try_from_edges(vec![
+            (0, '0', 1),
+            (1, '0', 0),
+            (1, '1', 0),
+        ], vec![1]);
+        assert_eq!(mismatch, Err(Error::AlphabetMismatch { state: 1, symbol: '1' }));
+
+        // The final state 2 has no outgoing edges.
+        let unconnected = Dfa::try_from_edges(vec![
+            (0, '0', 1),
+            (1, '0', 0),
+        ], vec![2]);
+        assert_eq!(unconnected, Err(Error::MissingTransition { state: 2, symbol: '0' }));
+
+        let out_of_range = Dfa::try_from_edges(vec![(0, '0', usize::MAX)], vec![]);
+        assert_eq!(out_of_range, Err(Error::StateOutOfRange(usize::MAX)));
+
+        // Rejected without attempting to allocate a table of that size.
+        let huge = Dfa::try_from_edges(vec![(0, '0', 1 << 60)], vec![]);
+        assert_eq!(huge, Err(Error::StateOutOfRange(1 << 60)));
+
+        let huge_final = Dfa::try_from_partial_edges(vec![(0, '0', 0)], vec![usize::MAX - 1]);
+        assert_eq!(huge_final, Err(Error::StateOutOfRange(usize::MAX - 1)));
+
+        let valid = Dfa::try_from_edges(vec![
+            (0, '0', 1),
+            (1, '0', 0),
+        ], vec![1]);
+        assert!(valid.unwrap().contains("0".chars()));
+
+        // States 1 and 2 are unused, which is fine without completeness.
+        let sparse = Dfa::try_from_partial_edges(vec![(0, 'a', 3)], vec![3]).unwrap();
+        assert!( sparse.contains("a".chars()));
+        assert!(!sparse.contains("aa".chars()));
+
+        // Over the empty alphabet, every state is trivially complete.
+        let empty_alphabet = Dfa::<char>::try_from_edges(vec![], vec![3]).unwrap();
+        assert!( empty_alphabet.is_complete());
+        assert!(!empty_alphabet.contains("".chars()));
+    }
+
+    #[test]
+    fn partial() {
+        // Accepts `a(ba)*`, without edges into the dead state.
+        let automaton = Dfa::from_partial_edges(vec![
+            (0, 'a', 1),
+            (1, 'b', 0),
+        ], vec![1]);
+
+        assert!(!automaton.is_complete());
+        assert!( automaton.contains("aba".chars()));
+        assert!(!automaton.contains("ab".chars()));
+        assert!(!automaton.contains("abb".chars()));
+        assert!(!automaton.contains("b".chars()));
+
+        let complete = automaton.complete();
+        assert!(complete.is_complete());
+        assert_eq!(complete.alphabet(), &['a', 'b']);
+        assert_eq!(complete.equivalent(&automaton), Ok(()));
+        assert!(complete.contains("aba".chars()));
+        assert!(!complete.contains("abb".chars()));
+
+        let minimal = automaton.minimized();
+        assert_eq!(minimal, complete.minimized());
+        assert_eq!(minimal, automaton.minimized_brzozowski());
+
+        let complement = !&automaton;
+        assert!( complement.contains("abb".chars()));
+        assert!(!complement.contains("aba".chars()));
+
+        let words_a = Dfa::from_partial_edges(vec![(0, 'a', 0)], vec![0]);
+        assert_eq!((&automaton & &words_a).minimized(), Dfa::from_edges(vec![
+            (0, 'a', 1),
+            (0, 'b', 2),
+            (1, 'a', 2),
+            (1, 'b', 2),
+            (2, 'a', 2),
+            (2, 'b', 2),
+        ], vec![1]));
+        assert_eq!(automaton.is_subset_of(&words_a), Err(vec!['a', 'b', 'a']));
+        assert!(!automaton.equivalent_hopcroft_karp(&words_a));
+        assert!(!automaton.pair_empty(&words_a, |lhs, rhs| lhs && rhs));
+
+        assert_eq!(
+            Dfa::try_from_partial_edges(vec![(0, 'a', 0), (0, 'a', 1)], vec![]),
+            Err(Error::DuplicateTransition { state: 0, symbol: 'a' }));
+    }
+
+    #[test]
+    fn unknown_symbols() {
+        // Accepts `a(ba)*`, without edges into the dead state.
+        let automaton = Dfa::from_partial_edges(vec![
+            (0, 'a', 1),
+            (1, 'b', 0),
+        ], vec![1]);
+
+        assert!(!automaton.contains("c".chars()));
+        assert!(!automaton.contains("abc".chars()));
+        assert_eq!(automaton.try_contains("aba".chars()), Ok(true));
+        assert_eq!(automaton.try_contains("abb".chars()), Ok(false));
+        assert_eq!(automaton.try_contains("abc".chars()),
+            Err(Error::UnknownSymbol { position: 2, symbol: 'c' }));
+        assert_eq!(automaton.try_contains("bbac".chars()),
+            Err(Error::UnknownSymbol { position: 3, symbol: 'c' }));
+    }
+
+    #[test]
+    fn reverse() {
+        // Accepts words ending in `01`.
+        let automaton = Dfa::from_edges(vec![
+            (0, '0', 1),
+            (0, '1', 0),
+            (1, '0', 1),
+            (1, '1', 2),
+            (2, '0', 1),
+            (2, '1', 0),
+        ], vec![2]);
+
+        let reversed = automaton.reverse();
+        assert!( reversed.contains("10".chars()));
+        assert!( reversed.contains("1011".chars()));
+        assert!(!reversed.contains("01".chars()));
+        assert!(!reversed.contains("".chars()));
+    }
+
+    #[test]
+    fn trim() {
+        // Accepts `ab*`, state 2 is unreachable and states 3, 4 are dead.
+        let automaton = Dfa::from_edges(vec![
+            (0, 'a', 1),
+            (0, 'b', 3),
+            (1, 'a', 4),
+            (1, 'b', 1),
+            (2, 'a', 1),
+            (2, 'b', 1),
+            (3, 'a', 4),
+            (3, 'b', 3),
+            (4, 'a', 4),
+            (4, 'b', 3),
+        ], vec![1]);
+
+        let (trimmed, renumbering) = automaton.trim();
+        assert_eq!(renumbering, vec![Some(0), Some(1), None, Some(2), Some(2)]);
+        assert!(trimmed.is_complete());
+        assert!(trimmed.equivalent(&automaton).is_ok());
+
+        // Without completion, the dead states are removed entirely.
+        let partial = Dfa::from_partial_edges(vec![
+            (0, 'a', 1),
+            (0, 'b', 2),
+            (1, 'b', 1),
+            (2, 'b', 2),
+        ], vec![1]);
+
+        let (trimmed, renumbering) = partial.trim();
+        assert_eq!(renumbering, vec![Some(0), Some(1), None]);
+        assert!(!trimmed.is_complete());
+        assert!( trimmed.contains("abb".chars()));
+        assert!(!trimmed.contains("b".chars()));
+
+        // The start state remains for the empty language.
+        let empty = Dfa::from_edges(vec![
+            (0, 'a', 1),
+            (1, 'a', 0),
+        ], vec![]);
+        let (trimmed, renumbering) = empty.trim();
+        assert_eq!(renumbering, vec![Some(0), Some(0)]);
+        assert!(trimmed.equivalent(&empty).is_ok());
+    }
+
+    #[test]
+    fn minimize() {
+        // Accepts words with an odd number of `1`, with redundant and unreachable states.
+        let automaton = Dfa::from_edges(vec![
+            (0, '0', 2),
+            (0, '1', 1),
+            (1, '0', 3),
+            (1, '1', 0),
+            (2, '0', 0),
+            (2, '1', 3),
+            (3, '0', 1),
+            (3, '1', 2),
+            (4, '0', 4),
+            (4, '1', 0),
+        ], vec![1, 3]);
+
+        let minimized = automaton.minimized();
+        assert_eq!(minimized, Dfa::from_edges(vec![
+            (0, '0', 0),
+            (0, '1', 1),
+            (1, '0', 1),
+            (1, '1', 0),
+        ], vec![1]));
+
+        assert!( minimized.contains("1".chars()));
+        assert!( minimized.contains("0100".chars()));
+        assert!(!minimized.contains("".chars()));
+        assert!(!minimized.contains("0110".chars()));
+    }
+
+    #[test]
+    fn minimize_canonical() {
+        // Accepts words whose length is divisible by 3, numbered differently.
+        let automaton_3 = Dfa::from_edges(vec![
+            (0, '.', 1),
+            (1, '.', 2),
+            (2, '.', 0),
+        ], vec![0]);
+
+        let automaton_6 = Dfa::from_edges(vec![
+            (0, '.', 5),
+            (5, '.', 1),
+            (1, '.', 3),
+            (3, '.', 4),
+            (4, '.', 2),
+            (2, '.', 0),
+        ], vec![0, 3]);
+
+        assert_eq!(automaton_3.minimized(), automaton_6.minimized());
+        assert_eq!(automaton_3.minimized(), automaton_3);
+
+        // All states are final, minimal automaton has a single state.
+        let universal = Dfa::from_edges(vec![
+            (0, '.', 1),
+            (1, '.', 0),
+        ], vec![0, 1]);
+
+        assert_eq!(universal.minimized(), Dfa::from_edges(vec![
+            (0, '.', 0),
+        ], vec![0]));
+    }
+
+    #[test]
+    fn minimize_brzozowski() {
+        let automata = vec![
+            // Words with an odd number of `1`, with redundant and unreachable states.
+            Dfa::from_edges(vec![
+                (0, '0', 2),
+                (0, '1', 1),
+                (1, '0', 3),
+                (1, '1', 0),
+                (2, '0', 0),
+                (2, '1', 3),
+                (3, '0', 1),
+                (3, '1', 2),
+                (4, '0', 4),
+                (4, '1', 0),
+            ], vec![1, 3]),
+            // Binary numbers divisible by 3.
+            Dfa::from_edges(vec![
+                (0, '0', 0),
+                (0, '1', 1),
+                (1, '0', 2),
+                (1, '1', 0),
+                (2, '0', 1),
+                (2, '1', 2),
+            ], vec![0]),
+            // The empty language.
+            Dfa::from_edges(vec![
+                (0, '0', 1),
+                (0, '1', 0),
+                (1, '0', 0),
+                (1, '1', 1),
+            ], vec![]),
+            // Words ending in `01`, from the powerset construction.
+            Nfa::from_edges(vec![
+                (0, Some('0'), 0),
+                (0, Some('1'), 0),
+                (0, Some('0'), 1),
+                (1, Some('1'), 2),
+            ], vec![2]).into_dfa(vec![]),
+        ];
+
+        for automaton in automata {
+            let brzozowski = automaton.minimized_brzozowski();
+            assert_eq!(brzozowski, automaton.minimized());
+            assert!(automaton.pair_empty(&brzozowski, |lhs, rhs| lhs != rhs));
+        }
+    }
 }
@@ -0,0 +1,147 @@
+//! Incremental matching of words, one symbol at a time.
+//!
+//! The matcher is a cursor on the states of an automaton. It can be fed from several buffers
+//! and reports as soon as no continuation of the input can be accepted anymore.
+use crate::{Alphabet, Error};
+use crate::deterministic::Target;
+use super::{Dfa, Node};
+
+/// A cursor driving a dfa symbol by symbol.
+///
+/// Creating a matcher determines the states from which a final state is reachable, which takes
+/// time linear in the size of the automaton. Cloning and resetting a matcher does not.
+#[derive(Clone, Debug)]
+pub struct Matcher<'a, A: Alphabet> {
+    automaton: &'a Dfa<A>,
+
+    /// Whether a final state is reachable from each state.
+    live: Vec<bool>,
+
+    /// The current state, `None` after a missing edge or a symbol outside the alphabet.
+    state: Option<Target>,
+}
+
+impl<'a, A: Alphabet> Matcher<'a, A> {
+    /// Create a matcher in the start state of the automaton.
+    pub fn new(automaton: &'a Dfa<A>) -> Self {
+        let live = automaton.live_states();
+
+        Matcher {
+            automaton,
+            live,
+            state: Some(Target::ZERO),
+        }
+    }
+
+    /// Advance by one symbol of the input.
+    ///
+    /// A missing edge or a symbol outside the alphabet leads into an implicit dead state, which
+    /// the matcher does not leave until it is reset.
+    pub fn step(&mut self, symbol: A) {
+        self.state = self.state.and_then(|state| self.automaton.graph
+            .edges(state).unwrap()
+            .target(symbol)
+            .unwrap_or(None));
+    }
+
+    /// Check whether the input so far is a word of the language.
+    pub fn is_accepting(&self) -> bool {
+        self.state.is_some_and(|state| self.automaton.finals.contains(&state))
+    }
+
+    /// Check whether no continuation of the input so far is a word of the language.
+    pub fn is_dead(&self) -> bool {
+        self.state.is_none_or(|state| !self.live[state.index()])
+    }
+
+    /// Return to the start state, as if no input had been consumed.
+    pub fn reset(&mut self) {
+        self.state = Some(Target::ZERO);
+    }
+
+    /// A snapshot of the current state.
+    ///
+    /// The implicit dead state, reached by a missing edge or an unknown symbol, is `None`.
+    pub fn state(&self) -> Option<Node> {
+        self.state.map(|state| Node(state.index()))
+    }
+
+    /// Continue from a previous snapshot of `state`.
+    ///
+    /// Fails when the state does not exist in the automaton.
+    pub fn resume(&mut self, state: Option<Node>) -> Result<(), Error<A>> {
+        self.state = match state {
+            None => None,
+            Some(Node(idx)) if idx < self.live.len() => Some(Target::make(idx)),
+            Some(Node(idx)) => return Err(Error::StateOutOfRange(idx)),
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_by_step() {
+        // Accepts `ab*c`, state 3 is a dead state.
+        let automaton = Dfa::from_edges(vec![
+            (0, 'a', 1),
+            (0, 'b', 3),
+            (0, 'c', 3),
+            (1, 'a', 3),
+            (1, 'b', 1),
+            (1, 'c', 2),
+            (2, 'a', 3),
+            (2, 'b', 3),
+            (2, 'c', 3),
+            (3, 'a', 3),
+            (3, 'b', 3),
+            (3, 'c', 3),
+        ], vec![2]);
+
+        let mut matcher = automaton.matcher();
+        assert!(!matcher.is_accepting());
+        assert!(!matcher.is_dead());
+
+        matcher.step('a');
+        matcher.step('b');
+        let snapshot = matcher.state();
+        matcher.step('c');
+        assert!( matcher.is_accepting());
+        assert!(!matcher.is_dead());
+
+        matcher.step('c');
+        assert!(!matcher.is_accepting());
+        assert!( matcher.is_dead());
+
+        matcher.resume(snapshot).unwrap();
+        assert_eq!(matcher.state(), Some(Node(1)));
+        matcher.step('b');
+        matcher.step('c');
+        assert!(matcher.is_accepting());
+
+        matcher.reset();
+        matcher.step('x');
+        assert_eq!(matcher.state(), None);
+        assert!(matcher.is_dead());
+
+        assert_eq!(matcher.resume(Some(Node(4))), Err(Error::StateOutOfRange(4)));
+    }
+
+    #[test]
+    fn partial() {
+        let automaton = Dfa::from_partial_edges(vec![
+            (0, 'a', 1),
+            (1, 'b', 0),
+        ], vec![1]);
+
+        let mut matcher = automaton.matcher();
+        matcher.step('a');
+        assert!(matcher.is_accepting());
+        matcher.step('a');
+        assert!(matcher.is_dead());
+        assert_eq!(matcher.state(), None);
+    }
+}
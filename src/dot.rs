@@ -179,7 +179,7 @@ impl<W: io::Write> Drop for GraphWriter<W> {
     }
 }
 
-impl<'a, W: Write> GraphWriter<&'a mut W> {
+impl<W: Write> GraphWriter<&mut W> {
     pub fn subgraph(&mut self, _name: Option<String>) -> GraphWriter<&mut W> {
         unimplemented!()
     }
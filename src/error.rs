@@ -0,0 +1,69 @@
+//! The error of fallible constructions.
+//!
+//! Most constructors panic on invalid input, which is the right choice when the automata are part
+//! of the program. Their `try_` counterparts instead return an `Error`, for input which is
+//! supplied at runtime.
+use std::error;
+use std::fmt;
+
+use crate::regex::Handle;
+
+/// Describes why an automaton or regex could not be constructed.
+///
+/// States are referred to by the indices used during construction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error<A> {
+    /// A state of a deterministic automaton has no edge for a symbol of the alphabet.
+    MissingTransition {
+        state: usize,
+        symbol: A,
+    },
+
+    /// A state of a deterministic automaton has more than one edge for the same symbol.
+    DuplicateTransition {
+        state: usize,
+        symbol: A,
+    },
+
+    /// A state has an edge with a symbol which is not part of the alphabet.
+    ///
+    /// The alphabet of a deterministic automaton is the set of symbols on the edges of its start
+    /// state.
+    AlphabetMismatch {
+        state: usize,
+        symbol: A,
+    },
+
+    /// A word contains a symbol which is not part of the alphabet of the automaton.
+    UnknownSymbol {
+        /// The index of the symbol within the word.
+        position: usize,
+        symbol: A,
+    },
+
+    /// A regex operation refers to a subexpression which does not exist.
+    InvalidHandle(Handle),
+
+    /// A state index which can not be represented.
+    StateOutOfRange(usize),
+}
+
+impl<A: fmt::Debug> fmt::Display for Error<A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MissingTransition { state, symbol } =>
+                write!(f, "state {} has no transition for symbol {:?}", state, symbol),
+            Error::DuplicateTransition { state, symbol } =>
+                write!(f, "state {} has more than one transition for symbol {:?}", state, symbol),
+            Error::AlphabetMismatch { state, symbol } =>
+                write!(f, "state {} has a transition for symbol {:?} outside the alphabet", state, symbol),
+            Error::UnknownSymbol { position, symbol } =>
+                write!(f, "symbol {:?} at position {} is not part of the alphabet", symbol, position),
+            Error::InvalidHandle(Handle(handle)) =>
+                write!(f, "regex handle {} does not refer to an existing subexpression", handle),
+            Error::StateOutOfRange(state) => write!(f, "state {} is out of range", state),
+        }
+    }
+}
+
+impl<A: fmt::Debug> error::Error for Error<A> { }
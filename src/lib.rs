@@ -1,11 +1,16 @@
 mod deterministic;
+mod error;
 mod nondeterministic;
+mod partition;
+mod union_find;
 
 pub mod dfa;
 pub mod dot;
 pub mod nfa;
 pub mod regex;
 
+pub use crate::error::Error;
+
 use std::fmt::Debug;
 use std::hash::Hash;
 
@@ -25,6 +30,7 @@ impl<T> Alphabet for T where T: Hash + Eq + Debug + Clone + Copy + Ord { }
 trait Ensure<T> {
     fn ensure_with<F>(&mut self, n: usize, new: F) where F: FnMut() -> T;
 
+    #[allow(unused)]
     fn ensure(&mut self, n: usize, item: T) where T: Clone {
         self.ensure_with(n, || item.clone())
     }
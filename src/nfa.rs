@@ -1,17 +1,20 @@
-use std::collections::{BTreeSet, HashSet, HashMap};
+use std::collections::{BTreeSet, HashSet, HashMap, VecDeque};
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
 use std::io::{self, Write};
-use std::iter::{Extend, FromIterator};
 
 use super::Alphabet;
 use super::dfa::Dfa;
 use super::dot::{Family, Edge as DotEdge, GraphWriter, Node as DotNode};
-use super::regex::{self, Regex, Op as RegOp};
-use super::nondeterministic::NonDeterministic;
+use super::regex::{self, Cached, Regex, Op as RegOp};
+use super::nondeterministic::{Builder, NonDeterministic};
+
+mod simulator;
+
+pub use self::simulator::Simulator;
 
 /// A node handle of an epsilon nfa.
-#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Debug)]
 pub struct Node(pub usize);
 
 /// A node handle of a regex nfa.
@@ -33,21 +36,82 @@ pub struct NfaRegex<A: Alphabet> {
 
     /// The regex fragments of all edges.
     regex: Regex<A>,
+
+    /// Final or accepting states.
+    finals: HashSet<Node>,
+}
+
+/// The order in which `Nfa::to_regex_with` eliminates states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EliminationOrder {
+    /// Eliminate states from the highest to the lowest index.
+    Reverse,
+
+    /// Eliminate the state with the fewest paths through it first.
+    ///
+    /// The number of paths is the product of in-degree and out-degree, not counting self-loops.
+    /// It is the number of edges created by the elimination of the state.
+    MinDegree,
+
+    /// Eliminate the state whose removal grows the expression the least first.
+    ///
+    /// This is the weight heuristic of Delgado and Morais. Each regex on an edge into or out of
+    /// the state is copied once for each new path, the self-loop is copied into all of them.
+    MinWeight,
 }
 
-struct MultiMap<K: Hash + Eq, V> {
-    inner: HashMap<K, Vec<V>>,
+/// Options for converting an nfa into a regex.
+#[derive(Clone, Debug)]
+pub struct RegexOptions {
+    /// The order of eliminating states, `MinWeight` by default.
+    pub order: EliminationOrder,
+
+    /// Use `.. RegexOptions::default()` to make the structs.
+    pub _non_exhaustive: (),
 }
 
 /// Symbol used during transformation to regex.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum EphermalSymbol {
     Start,
     Real(usize),
     End,
 }
 
-type EdgeKey = (EphermalSymbol, EphermalSymbol);
+/// The generalized automaton, with regexes on its edges, during state elimination.
+struct Elimination<A: Alphabet> {
+    cached: Cached<A>,
+
+    /// The alternatives of all parallel edges, merged into one regex.
+    edges: HashMap<(EphermalSymbol, EphermalSymbol), regex::Handle>,
+
+    /// The successors of each node, without the node itself.
+    outgoing: HashMap<EphermalSymbol, BTreeSet<EphermalSymbol>>,
+
+    /// The predecessors of each node, without the node itself.
+    incoming: HashMap<EphermalSymbol, BTreeSet<EphermalSymbol>>,
+
+    /// The size of each subexpression when written as a tree, saturating.
+    sizes: Vec<usize>,
+}
+
+/// The index of the pair and the edge label from which a pair was first reached.
+type Trace<A> = Option<(usize, Option<A>)>;
+
+/// The pairs explored by the inclusion check, pruned to the minimal ones.
+struct Antichain<A: Alphabet> {
+    /// A state, a set of states of the other automaton, and how the pair was reached.
+    pairs: Vec<(Node, BTreeSet<Node>, Trace<A>)>,
+
+    /// The indices of the minimal pairs for each state.
+    minimal: HashMap<Node, Vec<usize>>,
+
+    /// Whether each pair is still minimal.
+    alive: Vec<bool>,
+
+    /// The indices of pairs still to explore.
+    working: VecDeque<usize>,
+}
 
 trait InsertNew<T> {
     fn insert_new(&mut self, item: T) -> bool;
@@ -68,12 +132,14 @@ impl<A: Alphabet> Nfa<A> {
         A: Clone + Debug,
     {
         let mut builder = NonDeterministic::builder();
+        builder.ensure_nodes(0);
 
         edge_iter.into_iter().for_each(
             |edge| builder.insert(edge.0, &edge.1, edge.2));
 
         let finals = finals
             .into_iter()
+            .inspect(|&fin| builder.ensure_nodes(fin))
             .map(Node)
             .collect();
 
@@ -93,6 +159,300 @@ impl<A: Alphabet> Nfa<A> {
         }
     }
 
+    /// An automaton for the reversed language.
+    ///
+    /// The new start state 0 has epsilon transitions to all previously final states, all other
+    /// states are shifted by one and have their edges reversed. The only final state is the
+    /// previous start state, now 1.
+    ///
+    /// Reading a word backwards from some state of the original automaton corresponds to reading
+    /// it forwards from the shifted state. This allows searching for the start of a match from
+    /// its end.
+    pub fn reverse(&self) -> Self {
+        let mut builder = self.graph.reversed(1);
+        builder.ensure_nodes(1);
+
+        for &Node(fin) in self.finals.iter() {
+            builder.insert(0, &None, fin + 1);
+        }
+
+        Nfa {
+            graph: builder.finish(),
+            finals: vec![Node(1)].into_iter().collect(),
+        }
+    }
+
+    /// An automaton accepting the words of either automaton.
+    ///
+    /// The new start state 0 has epsilon transitions to both previous start states. The states of
+    /// `self` follow from 1, then those of `other`.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut builder = NonDeterministic::builder();
+        builder.ensure_nodes(0);
+        let left = self.splice(&mut builder, 1);
+        let right = other.splice(&mut builder, 1 + self.graph.nodes().len());
+
+        builder.insert(0, &None, 1);
+        builder.insert(0, &None, 1 + self.graph.nodes().len());
+
+        Nfa {
+            graph: builder.finish(),
+            finals: left.chain(right).collect(),
+        }
+    }
+
+    /// An automaton accepting a word of this automaton followed by a word of `other`.
+    ///
+    /// The states of `other` follow those of `self`, the final states of `self` have epsilon
+    /// transitions to the previous start state of `other`.
+    pub fn concat(&self, other: &Self) -> Self {
+        let offset = self.graph.nodes().len();
+        let mut builder = NonDeterministic::builder();
+        let left = self.splice(&mut builder, 0).collect::<Vec<_>>();
+        let right = other.splice(&mut builder, offset);
+
+        for Node(fin) in left {
+            builder.insert(fin, &None, offset);
+        }
+
+        Nfa {
+            graph: builder.finish(),
+            finals: right.collect(),
+        }
+    }
+
+    /// An automaton accepting any sequence of words of this automaton, including none.
+    ///
+    /// The new start state 0 is the only final state. It has an epsilon transition to the
+    /// previous start state, now 1, and all previous final states have one back to it. A fresh
+    /// start is required, as the previous start state could be reached again in the middle of a
+    /// word.
+    pub fn star(&self) -> Self {
+        let mut builder = NonDeterministic::builder();
+        builder.ensure_nodes(0);
+        let finals = self.splice(&mut builder, 1).collect::<Vec<_>>();
+
+        builder.insert(0, &None, 1);
+        for Node(fin) in finals {
+            builder.insert(fin, &None, 0);
+        }
+
+        Nfa {
+            graph: builder.finish(),
+            finals: vec![Node(0)].into_iter().collect(),
+        }
+    }
+
+    /// An automaton accepting any non-empty sequence of words of this automaton.
+    ///
+    /// States keep their numbering, all final states have an epsilon transition back to the start.
+    pub fn plus(&self) -> Self {
+        let mut builder = NonDeterministic::builder();
+        let finals = self.splice(&mut builder, 0).collect::<HashSet<_>>();
+
+        for &Node(fin) in finals.iter() {
+            builder.insert(fin, &None, 0);
+        }
+
+        Nfa {
+            graph: builder.finish(),
+            finals,
+        }
+    }
+
+    /// An automaton accepting the words of this automaton and the empty word.
+    ///
+    /// The new start state 0 is final and has an epsilon transition to the previous start state,
+    /// now 1.
+    pub fn optional(&self) -> Self {
+        let mut builder = NonDeterministic::builder();
+        builder.ensure_nodes(0);
+        let finals = self.splice(&mut builder, 1);
+        let finals = finals.chain(Some(Node(0))).collect();
+
+        builder.insert(0, &None, 1);
+
+        Nfa {
+            graph: builder.finish(),
+            finals,
+        }
+    }
+
+    /// An automaton accepting the words of both automata.
+    ///
+    /// The states are the pairs of states reachable in the synchronous product, numbered in
+    /// breadth first order from the pair of start states. Symbol edges are taken in both
+    /// automata at once while an epsilon transition of either automaton moves only its own
+    /// component. The product is never determinized, so it has at most `|self|·|other|` states.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut builder = NonDeterministic::builder();
+        let mut finals = HashSet::new();
+        let mut assigned = HashMap::new();
+        let mut working = VecDeque::new();
+
+        builder.ensure_nodes(0);
+        assigned.insert((0, 0), 0);
+        working.push_back((0, 0));
+
+        while let Some((left, right)) = working.pop_front() {
+            let from = assigned[&(left, right)];
+            if self.finals.contains(&Node(left)) && other.finals.contains(&Node(right)) {
+                finals.insert(Node(from));
+            }
+
+            let mut successors = Vec::new();
+            for (&symbol, new_left) in self.graph.edges(left).unwrap() {
+                match symbol {
+                    None => successors.push((None, (new_left, right))),
+                    Some(_) => {
+                        let mut edges = other.graph.edges(right).unwrap();
+                        edges.restrict_to(&symbol);
+                        successors.extend(edges.targets()
+                            .map(|new_right| (symbol, (new_left, new_right))));
+                    },
+                }
+            }
+
+            let mut epsilons = other.graph.edges(right).unwrap();
+            epsilons.restrict_to(&None);
+            successors.extend(epsilons.targets()
+                .map(|new_right| (None, (left, new_right))));
+
+            for (symbol, pair) in successors {
+                let next = assigned.len();
+                let to = *assigned.entry(pair).or_insert_with(|| {
+                    working.push_back(pair);
+                    next
+                });
+                builder.insert(from, &symbol, to);
+            }
+        }
+
+        Nfa {
+            graph: builder.finish(),
+            finals,
+        }
+    }
+
+    /// Copy all states and edges into a builder, shifting the states by `offset`.
+    ///
+    /// Returns the shifted final states.
+    fn splice<'a>(&'a self, builder: &mut Builder<Option<A>>, offset: usize)
+        -> impl Iterator<Item=Node> + 'a
+    {
+        builder.ensure_nodes(offset + self.graph.nodes().len() - 1);
+        for (from, edges) in self.graph.nodes() {
+            for (symbol, to) in edges {
+                builder.insert(from + offset, symbol, to + offset);
+            }
+        }
+
+        self.finals.iter().map(move |&Node(fin)| Node(fin + offset))
+    }
+
+    /// An equivalent automaton without epsilon transitions.
+    ///
+    /// States keep their numbering. Each state receives the symbol edges of all states in its
+    /// epsilon closure and becomes final when its closure contains a final state. States which
+    /// were only reachable through epsilon transitions may become unreachable.
+    pub fn remove_epsilon(&self) -> Self {
+        let count = self.graph.nodes().len();
+        let mut builder = NonDeterministic::builder();
+        let mut finals = HashSet::new();
+        builder.ensure_nodes(count.saturating_sub(1));
+
+        for from in 0..count {
+            let closure: BTreeSet<_> = self.epsilon_reach(Node(from));
+            if closure.iter().any(|state| self.finals.contains(state)) {
+                finals.insert(Node(from));
+            }
+
+            let edges = closure.iter()
+                .flat_map(|&Node(state)| self.graph.edges(state).unwrap())
+                .filter(|(symbol, _)| symbol.is_some())
+                .map(|(&symbol, to)| (symbol, to))
+                .collect::<BTreeSet<_>>();
+
+            for (symbol, to) in edges {
+                builder.insert(from, &symbol, to);
+            }
+        }
+
+        Nfa {
+            graph: builder.finish(),
+            finals,
+        }
+    }
+
+    /// Remove the states which are unreachable or from which no final state is reachable.
+    ///
+    /// The remaining states keep their relative order. The start state is always kept as 0, even
+    /// when the language is empty.
+    ///
+    /// Also returns the new index of each previous state, if it was kept.
+    pub fn trim(&self) -> (Self, Vec<Option<usize>>) {
+        let count = self.graph.nodes().len();
+        let reachable = Self::reachable(&self.graph, Some(0));
+        let reversed = self.graph.reversed(0).finish();
+        let live = Self::reachable(&reversed, self.finals.iter().map(|&Node(fin)| fin));
+
+        let mut renumbering = vec![None; count];
+        let mut next = 0;
+        for state in 0..count {
+            if state == 0 || (reachable[state] && live[state]) {
+                renumbering[state] = Some(next);
+                next += 1;
+            }
+        }
+
+        let mut builder = NonDeterministic::builder();
+        builder.ensure_nodes(next - 1);
+        for (from, edges) in self.graph.nodes() {
+            for (symbol, to) in edges {
+                if let (Some(from), Some(to)) = (renumbering[from], renumbering[to]) {
+                    builder.insert(from, symbol, to);
+                }
+            }
+        }
+
+        let finals = self.finals.iter()
+            .filter_map(|&Node(fin)| renumbering[fin])
+            .map(Node)
+            .collect();
+
+        let nfa = Nfa {
+            graph: builder.finish(),
+            finals,
+        };
+
+        (nfa, renumbering)
+    }
+
+    /// The states reachable from any of `start`, over all edges.
+    fn reachable<I>(graph: &NonDeterministic<Option<A>>, start: I) -> Vec<bool>
+        where I: IntoIterator<Item=usize>
+    {
+        let mut reachable = vec![false; graph.nodes().len()];
+        let mut working = Vec::new();
+        for state in start {
+            if !reachable[state] {
+                reachable[state] = true;
+                working.push(state);
+            }
+        }
+
+        while let Some(from) = working.pop() {
+            for (_, to) in graph.edges(from).unwrap() {
+                if !reachable[to] {
+                    reachable[to] = true;
+                    working.push(to);
+                }
+            }
+        }
+
+        reachable
+    }
+
     /// First collapse all output states (compress the automaton).
     ///     This is done by adding new initial/final state and
     ///     epsilon transition to/from the previous states.
@@ -131,114 +491,87 @@ impl<A: Alphabet> Nfa<A> {
     ///
     /// => O(|V|³) length, preprocessing not even included (although its
     /// growth factor is smaller).
+    ///
+    /// All intermediate terms are constructed with `Cached::insert_simplified`,
+    /// which avoids much of the growth in practice. States are removed in the
+    /// order of the default `RegexOptions`, see `to_regex_with` for choosing
+    /// another one.
     pub fn to_regex(&self) -> Regex<A> {
-        let mut cached = Regex::new().cached();
-
-        // The epsilon symobl.
-        let eps = cached.insert(RegOp::Epsilon);
-        // Edge->handle list lut.
-        let mut edges = MultiMap::default();
+        self.to_regex_with(RegexOptions::default())
+    }
 
+    /// Convert to a regex, eliminating states in the order chosen by `options`.
+    ///
+    /// The order does not change the language of the result but can change its
+    /// size considerably. Ties between states are broken towards the highest
+    /// index, so `EliminationOrder::Reverse` is the order of all earlier versions.
+    pub fn to_regex_with(&self, options: RegexOptions) -> Regex<A> {
         use self::EphermalSymbol::{Start, Real, End};
 
-        edges.insert((Start, Real(0)), eps);
-        self.finals.iter().for_each(|&Node(real)| 
-            edges.insert((Real(real), End), eps));
+        let mut elimination = Elimination::new();
+        let eps = elimination.cached.insert(RegOp::Epsilon);
+
+        elimination.insert(Start, Real(0), eps);
+        self.finals.iter().for_each(|&Node(real)|
+            elimination.insert(Real(real), End, eps));
 
         for (real, node_edges) in self.graph.nodes() {
             for (symbol, target) in node_edges {
-                let key = (Real(real), Real(target));
                 let handle = match symbol {
-                    Some(symbol) => cached.insert(RegOp::Match(*symbol)),
+                    Some(symbol) => elimination.cached.insert(RegOp::Match(*symbol)),
                     None => eps,
                 };
-                edges.insert(key, handle);
+                elimination.insert(Real(real), Real(target), handle);
             }
         }
 
         // Remove intermediate nodes one-by-one
-        (0..self.graph.nodes().len()).rev().for_each(|real_to_delete| {
-            // 1. Merge phase
-            edges.inner.values_mut().for_each(|alternatives| 
-                if let Some(first) = alternatives.pop() {
-                    let alt = alternatives.iter().cloned()
-                        .fold(first, |alt1, alt2| 
-                            cached.insert(RegOp::Or(alt1, alt2)));
-                    alternatives.clear();
-                    alternatives.push(alt);
-                });
-
-
-            // 2. Removal phase
-            // 2.1 Separate all edges going to the node.
-            let start_to = edges.inner.remove_entry(&(Start, Real(real_to_delete)));
-            let to = (0..real_to_delete)
-                .map(|from| (Real(from), Real(real_to_delete)))
-                .filter_map(|key| edges.inner.remove_entry(&key))
-                .chain(start_to)
-                .filter_map(Self::get_single)
-                .collect::<Vec<_>>();
-
-            // 2.2 Separate all edges going out from the node.
-            let from_to_end = edges.inner.remove_entry(&(Real(real_to_delete), End));
-            let from = (0..real_to_delete)
-                .map(|to| (Real(real_to_delete), Real(to)))
-                .filter_map(|key| edges.inner.remove_entry(&key))
-                .chain(from_to_end)
-                .filter_map(Self::get_single)
-                .collect::<Vec<_>>();
-
-            // 2.3 Get the self-loop edge if it exists.
-            let self_loop = edges.inner
-                .remove_entry(&(Real(real_to_delete), Real(real_to_delete)))
-                .and_then(Self::get_single);
-
-            // ... and turn it into its `star` variant.
-            let self_star = self_loop.map(|(_, handle)| cached.insert(RegOp::Star(handle)));
-
-            // 2.4 Insert new paths for each going through.
-            for ((from_node, _), from_handle) in to.iter().cloned() {
-                for((_, to_node), to_handle) in from.iter().cloned() {
-                    let from_to_handle = if let Some(self_star) = self_star {
-                        let first_half = cached.insert(RegOp::Concat(from_handle, self_star));
-                        cached.insert(RegOp::Concat(first_half, to_handle))
-                    } else {
-                        cached.insert(RegOp::Concat(from_handle, to_handle))
-                    };
-                    edges.insert((from_node, to_node), from_to_handle);
-                }
-            }
-        });
+        let mut remaining = (0..self.graph.nodes().len()).collect::<BTreeSet<_>>();
+        while let Some(state) = elimination.choose(options.order, &remaining) {
+            remaining.remove(&state);
+            elimination.eliminate(Real(state));
+        }
 
-        let start_to_end = edges.inner.remove_entry(&(Start, End))
-            .expect("Ephermal start to end node should be the only left");
-        let start_to_end = Self::get_single(start_to_end)
-            .expect("Start to end path must exist").1;
-        assert!(edges.inner.is_empty());
+        // Without any path to a final state, the language is empty.
+        let start_to_end = elimination.edges.remove(&(Start, End))
+            .unwrap_or_else(|| elimination.cached.insert(RegOp::Empty));
+        assert!(elimination.edges.is_empty(), "Ephermal start to end node should be the only left");
 
-        let regex = cached.into_inner();
-        assert_eq!(Some(start_to_end), regex.root(), "Start to end must be the regex root");
-        regex
+        elimination.cached.into_inner().subexpression(start_to_end)
     }
 
     /// Convert to a dfa using the powerset construction.
     ///
     /// Since the alphabet can not be deduced purely from transitions, `alphabet_extension`
     /// provides a way to indicate additional symbols.
+    ///
+    /// States of the dfa are identified with sets of important nfa states, those which are final
+    /// or have an outgoing edge labeled with a symbol. The other states do not influence the
+    /// language of a set. Thus, the dfa for the reversal of a reachable dfa is already minimal.
     pub fn into_dfa<I: IntoIterator<Item=A>>(self, alphabet_extension: I) -> Dfa<A> {
+        let important = self.graph.nodes()
+            .map(|(idx, mut edges)| self.finals.contains(&Node(idx))
+                || edges.any(|(symbol, _)| symbol.is_some()))
+            .collect::<Vec<_>>();
+
         // The epsilon transition closure of reachable nodes.
-        let initial_state: BTreeSet<_> = self.epsilon_reach(Node(0));
+        let mut initial_state: BTreeSet<_> = self.epsilon_reach(Node(0));
+        initial_state.retain(|&Node(idx)| important[idx]);
         let alphabet = self.graph.alphabet()
             .iter()
             .cloned()
-            .filter_map(|x| x)
+            .flatten()
             .chain(alphabet_extension)
-            .collect::<Vec<_>>();
+            .collect::<BTreeSet<_>>();
+
+        let mut finals = Vec::new();
+        if initial_state.iter().any(|st| self.finals.contains(st)) {
+            finals.push(0);
+        }
 
         let mut state_map = vec![(initial_state.clone(), 0)].into_iter().collect::<HashMap<_, _>>();
         let mut pending = vec![initial_state];
         let mut edges = Vec::new();
-        let mut finals = Vec::new();
 
         while let Some(next) = pending.pop() {
             let from = state_map[&next];
@@ -251,9 +584,10 @@ impl<A: Alphabet> Nfa<A> {
                     })
                     .collect::<HashSet<_>>();
 
-                let closure = basic.into_iter()
+                let mut closure = basic.into_iter()
                     .map(|state| self.epsilon_reach(Node(state)))
                     .fold(BTreeSet::new(), |left, right| left.union(&right).cloned().collect());
+                closure.retain(|&Node(idx)| important[idx]);
 
                 let is_final = closure.iter().any(|st| self.finals.contains(st));
                 let new_index = state_map.len();
@@ -278,7 +612,7 @@ impl<A: Alphabet> Nfa<A> {
     }
 
     /// Write the nfa into the dot format.
-    pub fn write_to(&self, output: &mut Write) -> io::Result<()> 
+    pub fn write_to(&self, output: &mut dyn Write) -> io::Result<()> 
         where for<'a> &'a A: Display
     {
         let mut writer = GraphWriter::new(output, Family::Directed, None)?;
@@ -312,33 +646,95 @@ impl<A: Alphabet> Nfa<A> {
 
     /// Checks if the input word is contained in the language.
     ///
-    /// The check is realized by performing a dynamic powerset construction of the resulting dfa.
-    /// For simplicity, previous states are not stored anywhere, resulting in abysmal expected
-    /// runtime for anything but the smallest cases.
-    ///
-    /// Consider converting the nfa to an equivalent dfa before querying, especially when
-    /// performing multiple successive queries.
+    /// The check simulates the automaton on all active states at once, see `Simulator`. Consider
+    /// converting the nfa to an equivalent dfa before querying, especially when performing many
+    /// successive queries, or reuse a simulator with a cache.
     pub fn contains<I: IntoIterator<Item=A>>(&self, sequence: I) -> bool {
-        // The epsilon transition closure of reachable nodes.
-        let mut states: HashSet<_> = self.epsilon_reach(Node(0));
-
+        let mut simulator = self.simulator();
         for ch in sequence {
-            let next = states.into_iter()
-                .flat_map(|Node(idx)| {
-                    let mut edges = self.graph.edges(idx).unwrap();
-                    edges.restrict_to(&Some(ch));
-                    edges.targets()
-                })
-                .collect::<HashSet<_>>();
+            simulator.step(ch);
+            if simulator.is_dead() {
+                return false;
+            }
+        }
+
+        simulator.is_accepting()
+    }
+
+    /// A simulator to match words incrementally, starting in the start state.
+    pub fn simulator(&self) -> Simulator<'_, A> {
+        Simulator::new(self)
+    }
+
+    /// Check whether every word accepted by this automaton is also accepted by `other`.
+    ///
+    /// Explores the subset construction of `other` on the fly, paired with single states of this
+    /// automaton. A pair is pruned when a pair with the same state and a subset of its states was
+    /// already found, since every word rejected from the larger set is also rejected from the
+    /// smaller one. These minimal pairs form an antichain which is often much smaller than the
+    /// reachable part of the powerset.
+    ///
+    /// On failure, returns a word accepted by this automaton but not by `other`. It is short but
+    /// not necessarily the shortest such word.
+    pub fn is_subset_of(&self, other: &Self) -> Result<(), Vec<A>> {
+        let mut search = Antichain::default();
+        search.insert(Node(0), other.epsilon_reach(Node(0)), None);
 
-            let epsilon_reach = next.into_iter()
-                .map(|state| self.epsilon_reach(Node(state)))
-                .fold(HashSet::new(), |left, right| left.union(&right).cloned().collect());
+        while let Some(idx) = search.pop() {
+            let (Node(state), ref set, _) = search.pairs[idx];
 
-            states = epsilon_reach;
+            if self.finals.contains(&Node(state)) && !set.iter().any(|st| other.finals.contains(st)) {
+                return Err(search.word(idx));
+            }
+
+            let successors = self.graph.edges(state).unwrap()
+                .map(|(&symbol, target)| match symbol {
+                    None => (Node(target), set.clone(), symbol),
+                    Some(ch) => (Node(target), other.successors(set, ch), symbol),
+                })
+                .collect::<Vec<_>>();
+
+            for (target, set, symbol) in successors {
+                search.insert(target, set, Some((idx, symbol)));
+            }
         }
 
-        !states.is_disjoint(&self.finals)
+        Ok(())
+    }
+
+    /// Check whether the automaton accepts all words over its alphabet.
+    ///
+    /// Since the alphabet can not be deduced purely from transitions, `alphabet_extension`
+    /// provides a way to indicate additional symbols. This is the inclusion of the universal
+    /// language in this automaton, see `is_subset_of`.
+    ///
+    /// On failure, returns a word rejected by the automaton.
+    pub fn is_universal<I: IntoIterator<Item=A>>(&self, alphabet_extension: I) -> Result<(), Vec<A>> {
+        let alphabet = self.graph.alphabet()
+            .iter()
+            .cloned()
+            .flatten()
+            .chain(alphabet_extension)
+            .collect::<BTreeSet<_>>();
+        let universal = Nfa::from_edges(
+            alphabet.into_iter().map(|symbol| (0, Some(symbol), 0)),
+            vec![0]);
+        universal.is_subset_of(self)
+    }
+
+    /// The states reachable from a set of states with a symbol, followed by epsilon transitions.
+    fn successors(&self, states: &BTreeSet<Node>, symbol: A) -> BTreeSet<Node> {
+        let mut reached = BTreeSet::new();
+        for &Node(idx) in states.iter() {
+            let mut edges = self.graph.edges(idx).unwrap();
+            edges.restrict_to(&Some(symbol));
+            for target in edges.targets() {
+                if !reached.contains(&Node(target)) {
+                    reached.extend(self.epsilon_reach::<BTreeSet<_>>(Node(target)));
+                }
+            }
+        }
+        reached
     }
 
     /// All the state reachable purely by epsilon transitions.
@@ -361,12 +757,6 @@ impl<A: Alphabet> Nfa<A> {
 
         reached
     }
-
-    fn get_single((key, mut val): (EdgeKey, Vec<regex::Handle>)) 
-        -> Option<(EdgeKey, regex::Handle)> 
-    {
-        val.pop().map(|val| (key, val))
-    }
 }
 
 /// A non-deterministic finite automaton with regex transition guards.
@@ -384,8 +774,21 @@ impl<A: Alphabet> NfaRegex<A> {
     /// 0 ––a+b–> 1   >  0 |    1
     ///                    \–b–/
     /// ```
+    ///
+    /// Each edge is expanded into a separate fragment between its nodes, with new states appended
+    /// after the existing ones.
     pub fn into_nfa(self) -> Nfa<A> {
-        unimplemented!()
+        let mut builder = NonDeterministic::builder();
+        let mut next = self.graph.nodes().len().max(1);
+        builder.ensure_nodes(next - 1);
+
+        for (from, edges) in self.graph.nodes() {
+            for (&idx, to) in edges {
+                self.regex.thompson(regex::Handle(idx), from, to, &mut builder, &mut next);
+            }
+        }
+
+        Nfa::from_nondeterministic(builder.finish(), self.finals)
     }
 }
 
@@ -417,43 +820,194 @@ impl<A: Alphabet> From<Nfa<A>> for NfaRegex<A> {
         NfaRegex {
             graph: graph.finish(),
             regex,
+            finals: automaton.finals,
         }
     }
 }
 
-impl<K: Hash + Eq, V> MultiMap<K, V> {
-    pub fn insert(&mut self, key: K, value: V) {
-        let mapped = self.inner.entry(key)
-            .or_insert_with(Vec::new);
-        mapped.push(value)
+impl<A: Alphabet> Elimination<A> {
+    fn new() -> Self {
+        Elimination {
+            cached: Regex::new().cached(),
+            edges: HashMap::new(),
+            outgoing: HashMap::new(),
+            incoming: HashMap::new(),
+            sizes: Vec::new(),
+        }
+    }
+
+    /// Add an edge, as an alternative to an existing edge between the same nodes.
+    fn insert(&mut self, from: EphermalSymbol, to: EphermalSymbol, handle: regex::Handle) {
+        let handle = match self.edges.get(&(from, to)) {
+            Some(&existing) => self.cached.insert_simplified(RegOp::Or(existing, handle)),
+            None => handle,
+        };
+        self.edges.insert((from, to), handle);
+
+        if from != to {
+            self.outgoing.entry(from).or_default().insert(to);
+            self.incoming.entry(to).or_default().insert(from);
+        }
+    }
+
+    /// Choose the next state to eliminate from the remaining ones.
+    fn choose(&mut self, order: EliminationOrder, remaining: &BTreeSet<usize>) -> Option<usize> {
+        // Iterating backwards lets `min_by_key` break ties towards the highest index.
+        match order {
+            EliminationOrder::Reverse => remaining.iter().next_back().cloned(),
+            EliminationOrder::MinDegree => remaining.iter().rev().cloned()
+                .min_by_key(|&state| {
+                    let (incoming, outgoing) = self.degrees(EphermalSymbol::Real(state));
+                    incoming.saturating_mul(outgoing)
+                }),
+            EliminationOrder::MinWeight => remaining.iter().rev().cloned()
+                .min_by_key(|&state| self.weight(EphermalSymbol::Real(state))),
+        }
+    }
+
+    fn degrees(&self, node: EphermalSymbol) -> (usize, usize) {
+        let incoming = self.incoming.get(&node).map_or(0, BTreeSet::len);
+        let outgoing = self.outgoing.get(&node).map_or(0, BTreeSet::len);
+        (incoming, outgoing)
+    }
+
+    /// The growth in size of all edge regexes when eliminating the node.
+    fn weight(&mut self, node: EphermalSymbol) -> usize {
+        let (in_degree, out_degree) = self.degrees(node);
+        if in_degree == 0 || out_degree == 0 {
+            return 0;
+        }
+
+        let incoming = self.incoming.get(&node).into_iter().flatten()
+            .map(|&from| self.edges[&(from, node)])
+            .collect::<Vec<_>>();
+        let outgoing = self.outgoing.get(&node).into_iter().flatten()
+            .map(|&to| self.edges[&(node, to)])
+            .collect::<Vec<_>>();
+        let self_loop = self.edges.get(&(node, node)).cloned();
+
+        let incoming = incoming.into_iter()
+            .map(|handle| self.size(handle).saturating_mul(out_degree - 1))
+            .fold(0, usize::saturating_add);
+        let outgoing = outgoing.into_iter()
+            .map(|handle| self.size(handle).saturating_mul(in_degree - 1))
+            .fold(0, usize::saturating_add);
+        let self_loop = self_loop
+            .map(|handle| self.size(handle).saturating_mul(in_degree * out_degree - 1))
+            .unwrap_or(0);
+
+        incoming.saturating_add(outgoing).saturating_add(self_loop)
+    }
+
+    fn size(&mut self, handle: regex::Handle) -> usize {
+        let regex = self.cached.inner();
+        let sizes = &mut self.sizes;
+        while sizes.len() <= handle.0 {
+            let size = match *regex.op(regex::Handle(sizes.len())) {
+                RegOp::Empty | RegOp::Epsilon | RegOp::Match(_) => 1,
+                RegOp::Star(sub) => sizes[sub.0].saturating_add(1),
+                RegOp::Or(a, b) | RegOp::Concat(a, b) => sizes[a.0]
+                    .saturating_add(sizes[b.0])
+                    .saturating_add(1),
+            };
+            sizes.push(size);
+        }
+        sizes[handle.0]
+    }
+
+    /// Remove the node, shortcutting every path through it.
+    fn eliminate(&mut self, node: EphermalSymbol) {
+        let self_loop = self.edges.remove(&(node, node));
+        let self_star = self_loop.map(|handle| self.cached.insert_simplified(RegOp::Star(handle)));
+
+        let incoming = self.incoming.remove(&node).unwrap_or_default().into_iter()
+            .map(|from| {
+                self.outgoing.get_mut(&from).unwrap().remove(&node);
+                (from, self.edges.remove(&(from, node)).unwrap())
+            })
+            .collect::<Vec<_>>();
+        let outgoing = self.outgoing.remove(&node).unwrap_or_default().into_iter()
+            .map(|to| {
+                self.incoming.get_mut(&to).unwrap().remove(&node);
+                (to, self.edges.remove(&(node, to)).unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        for &(from, from_handle) in incoming.iter() {
+            for &(to, to_handle) in outgoing.iter() {
+                let from_to_handle = if let Some(self_star) = self_star {
+                    let first_half = self.cached.insert_simplified(RegOp::Concat(from_handle, self_star));
+                    self.cached.insert_simplified(RegOp::Concat(first_half, to_handle))
+                } else {
+                    self.cached.insert_simplified(RegOp::Concat(from_handle, to_handle))
+                };
+                self.insert(from, to, from_to_handle);
+            }
+        }
     }
 }
 
-impl<K: Hash + Eq, V> Default for MultiMap<K, V> {
+impl Default for RegexOptions {
     fn default() -> Self {
-        MultiMap {
-            inner: Default::default(),
+        RegexOptions {
+            order: EliminationOrder::MinWeight,
+            _non_exhaustive: (),
         }
     }
 }
 
-impl<K: Hash + Eq, V> FromIterator<(K, V)> for MultiMap<K, V> {
-    fn from_iter<T>(iter: T) -> Self
-        where T: IntoIterator<Item = (K, V)> 
-    {
-        let mut set = MultiMap::default();
-        iter.into_iter()
-            .for_each(|(key, value)| set.insert(key, value));
-        set
+impl<A: Alphabet> Antichain<A> {
+    /// Add a pair unless a pair with a subset of its states is already known.
+    ///
+    /// Pairs with a superset of the states are no longer considered minimal.
+    fn insert(&mut self, state: Node, set: BTreeSet<Node>, trace: Trace<A>) {
+        let pairs = &self.pairs;
+        let alive = &mut self.alive;
+        let minimal = self.minimal.entry(state).or_default();
+        if minimal.iter().any(|&idx| pairs[idx].1.is_subset(&set)) {
+            return;
+        }
+
+        minimal.retain(|&idx| {
+            alive[idx] = !set.is_subset(&pairs[idx].1);
+            alive[idx]
+        });
+        minimal.push(pairs.len());
+        self.working.push_back(pairs.len());
+        self.alive.push(true);
+        self.pairs.push((state, set, trace));
+    }
+
+    /// The next pair to explore, skipping those which are no longer minimal.
+    fn pop(&mut self) -> Option<usize> {
+        while let Some(idx) = self.working.pop_front() {
+            if self.alive[idx] {
+                return Some(idx);
+            }
+        }
+        None
+    }
+
+    /// The word along which a pair was reached.
+    fn word(&self, mut idx: usize) -> Vec<A> {
+        let mut word = Vec::new();
+        while let Some((previous, symbol)) = self.pairs[idx].2 {
+            word.extend(symbol);
+            idx = previous;
+        }
+        word.reverse();
+        word
     }
 }
 
-impl<K: Hash + Eq, V> Extend<(K, V)> for MultiMap<K, V> {
-    fn extend<T>(&mut self, iter: T)
-        where T: IntoIterator<Item = (K, V)> 
-    {
-        iter.into_iter()
-            .for_each(|(key, value)| self.insert(key, value));
+impl<A: Alphabet> Default for Antichain<A> {
+    fn default() -> Self {
+        Antichain {
+            pairs: Vec::new(),
+            minimal: HashMap::new(),
+            alive: Vec::new(),
+            working: VecDeque::new(),
+        }
     }
 }
 
@@ -514,6 +1068,22 @@ mod tests {
         assert!(!automaton.contains("2".chars()));
     }
 
+    #[test]
+    fn node_count() {
+        // More edges than states.
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('a'), 1),
+            (0, Some('b'), 1),
+            (1, Some('a'), 0),
+        ], vec![1]);
+
+        let mut nodes = automaton.graph.nodes();
+        assert_eq!(nodes.len(), 2);
+        nodes.next();
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes.count(), 1);
+    }
+
     #[test]
     fn convert_to_dfa() {
         let automaton = Nfa::from_edges(vec![
@@ -532,4 +1102,328 @@ mod tests {
         assert!(!automaton.contains("11".chars()));
         assert!(!automaton.contains("2".chars()));
     }
+
+    #[test]
+    fn convert_to_dfa_subsets() {
+        // A final start state which is not reached again.
+        let automaton = Nfa::from_edges(vec![(0, Some('a'), 1)], vec![0]);
+        let dfa = automaton.into_dfa(vec!['a']);
+        assert!( dfa.contains("".chars()));
+        assert!(!dfa.contains("a".chars()));
+
+        // The epsilon-only start state does not distinguish the initial subset.
+        let automaton = Nfa::from_edges(vec![
+            (0, None, 1),
+            (1, Some('a'), 1),
+        ], vec![1]);
+        assert_eq!(automaton.into_dfa(vec![]), Dfa::from_edges(vec![(0, 'a', 0)], vec![0]));
+    }
+
+    #[test]
+    fn regex_edges() {
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('0'), 0),
+            (0, None, 1),
+            (0, Some('1'), 1),
+            (1, Some('0'), 0),
+        ], vec![1]);
+
+        let automaton = NfaRegex::from(automaton).into_nfa();
+
+        assert!( automaton.contains("".chars()));
+        assert!( automaton.contains("1".chars()));
+        assert!( automaton.contains("1001".chars()));
+        assert!( automaton.contains("0000".chars()));
+        assert!(!automaton.contains("11".chars()));
+        assert!(!automaton.contains("2".chars()));
+    }
+
+    #[test]
+    fn inclusion() {
+        // Words ending in `1`.
+        let ending = Nfa::from_edges(vec![
+            (0, Some('0'), 0),
+            (0, Some('1'), 0),
+            (0, Some('1'), 1),
+        ], vec![1]);
+
+        // Words containing `1`, with an epsilon transition.
+        let containing = Nfa::from_edges(vec![
+            (0, Some('0'), 0),
+            (0, Some('1'), 0),
+            (0, Some('1'), 1),
+            (1, None, 2),
+            (2, Some('0'), 2),
+            (2, Some('1'), 2),
+        ], vec![2]);
+
+        assert_eq!(ending.is_subset_of(&containing), Ok(()));
+        assert_eq!(ending.is_subset_of(&ending), Ok(()));
+
+        let word = containing.is_subset_of(&ending).unwrap_err();
+        assert_eq!(word, vec!['1', '0']);
+    }
+
+    #[test]
+    fn antichain_eviction() {
+        let set = |states: &[usize]| states.iter().cloned().map(Node).collect::<BTreeSet<_>>();
+
+        let mut search = Antichain::<char>::default();
+        search.insert(Node(0), set(&[1, 2]), None);
+        search.insert(Node(1), set(&[1, 2]), None);
+        // Subsumes the first pair, which is then never explored.
+        search.insert(Node(0), set(&[1]), None);
+        // Subsumed by the previous pair.
+        search.insert(Node(0), set(&[1, 3]), None);
+
+        assert_eq!(search.pop(), Some(1));
+        assert_eq!(search.pop(), Some(2));
+        assert_eq!(search.pop(), None);
+    }
+
+    #[test]
+    fn universality() {
+        // All words, one symbol only reachable through an epsilon transition.
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('0'), 0),
+            (0, None, 1),
+            (1, Some('1'), 0),
+        ], vec![0, 1]);
+
+        assert_eq!(automaton.is_universal(None), Ok(()));
+        assert_eq!(automaton.is_universal(Some('2')), Err(vec!['2']));
+
+        // Words without `11`.
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('0'), 0),
+            (0, Some('1'), 1),
+            (1, Some('0'), 0),
+        ], vec![0, 1]);
+
+        assert_eq!(automaton.is_universal(None), Err(vec!['1', '1']));
+        assert_eq!(Nfa::<char>::from_edges(vec![], vec![]).is_universal(None), Err(vec![]));
+    }
+
+    #[test]
+    fn remove_epsilon() {
+        // Words of `(0|1)*1`, `0*` and `0*1*0`, joined by epsilon transitions.
+        let automaton = Nfa::from_edges(vec![
+            (0, None, 1),
+            (0, None, 3),
+            (1, Some('0'), 1),
+            (1, Some('1'), 1),
+            (1, Some('1'), 2),
+            (3, Some('0'), 3),
+            (3, None, 4),
+            (4, Some('1'), 4),
+            (4, Some('0'), 5),
+            (5, None, 6),
+        ], vec![2, 3, 6]);
+
+        let without = automaton.remove_epsilon();
+        assert!(without.graph.nodes().all(|(_, mut edges)| edges.all(|(symbol, _)| symbol.is_some())));
+        assert!(without.finals.contains(&Node(0)));
+        assert!(without.finals.contains(&Node(5)));
+
+        let alphabet = ['0', '1'];
+        assert_eq!(
+            without.into_dfa(alphabet.iter().cloned()).minimized(),
+            automaton.into_dfa(alphabet.iter().cloned()).minimized());
+    }
+
+    #[test]
+    fn combinators() {
+        // Accepts `ab`.
+        let ab = Nfa::from_edges(vec![
+            (0, Some('a'), 1),
+            (1, Some('b'), 2),
+        ], vec![2]);
+
+        // Accepts `c+`, through an epsilon transition back to the start state.
+        let c = Nfa::from_edges(vec![
+            (0, Some('c'), 1),
+            (1, None, 0),
+            (1, Some('c'), 2),
+        ], vec![1, 2]);
+
+        let union = ab.union(&c);
+        assert!( union.contains("ab".chars()));
+        assert!( union.contains("cc".chars()));
+        assert!(!union.contains("abc".chars()));
+
+        let concat = ab.concat(&c);
+        assert!( concat.contains("abc".chars()));
+        assert!( concat.contains("abccc".chars()));
+        assert!(!concat.contains("ab".chars()));
+        assert!(!concat.contains("cab".chars()));
+
+        let star = ab.star();
+        assert!( star.contains("".chars()));
+        assert!( star.contains("ababab".chars()));
+        assert!(!star.contains("aba".chars()));
+
+        let plus = ab.plus();
+        assert!(!plus.contains("".chars()));
+        assert!( plus.contains("abab".chars()));
+        assert!(!plus.contains("abb".chars()));
+
+        let optional = ab.optional();
+        assert!( optional.contains("".chars()));
+        assert!( optional.contains("ab".chars()));
+        assert!(!optional.contains("abab".chars()));
+
+        // Composed as `(ab|c+)*(c+)?`, compared with an equivalent regex.
+        let composed = ab.union(&c).star().concat(&c.optional());
+        let regex = Regex::parse("(ab|c+)*c?").unwrap();
+        let alphabet = ['a', 'b', 'c'];
+        assert_eq!(
+            composed.into_dfa(alphabet.iter().cloned()).minimized(),
+            regex.to_dfa(alphabet.iter().cloned()).minimized());
+    }
+
+    #[test]
+    fn intersection() {
+        // Words with an even number of `a`, with epsilon transitions.
+        let even = Nfa::from_edges(vec![
+            (0, Some('b'), 0),
+            (0, Some('a'), 1),
+            (1, Some('b'), 1),
+            (1, Some('a'), 2),
+            (2, None, 0),
+        ], vec![0]);
+
+        // Words ending in `ab`.
+        let ending = Regex::parse("(a|b)*ab").unwrap().to_nfa();
+
+        let both = even.intersection(&ending);
+        assert!( both.contains("aab".chars()));
+        assert!( both.contains("babbab".chars()));
+        assert!(!both.contains("ab".chars()));
+        assert!(!both.contains("aa".chars()));
+        assert!(!both.contains("".chars()));
+
+        let alphabet = ['a', 'b'];
+        let expected = even.into_dfa(alphabet.iter().cloned())
+            .intersection(&ending.into_dfa(alphabet.iter().cloned()));
+        assert_eq!(
+            both.into_dfa(alphabet.iter().cloned()).minimized(),
+            expected.minimized());
+    }
+
+    #[test]
+    fn reverse() {
+        // Words starting with `ab`, with epsilon transitions.
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('a'), 1),
+            (1, None, 2),
+            (2, Some('b'), 3),
+            (3, Some('a'), 3),
+            (3, Some('b'), 3),
+        ], vec![3]);
+
+        let reversed = automaton.reverse();
+        for word in ["ab", "abba", "ba", "a", "bab", ""].iter() {
+            let backwards = word.chars().rev().collect::<String>();
+            assert_eq!(reversed.contains(backwards.chars()), automaton.contains(word.chars()), "{}", word);
+        }
+
+        let alphabet = ['a', 'b'];
+        assert_eq!(
+            reversed.reverse().into_dfa(alphabet.iter().cloned()).minimized(),
+            automaton.into_dfa(alphabet.iter().cloned()).minimized());
+    }
+
+    #[test]
+    fn trim() {
+        // Accepts `ab`, state 2 is dead and state 4 is unreachable.
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('a'), 1),
+            (0, Some('a'), 2),
+            (1, None, 3),
+            (2, Some('b'), 2),
+            (3, Some('b'), 5),
+            (4, Some('a'), 5),
+        ], vec![5]);
+
+        let (trimmed, renumbering) = automaton.trim();
+        assert_eq!(renumbering, vec![Some(0), Some(1), None, Some(2), None, Some(3)]);
+        assert_eq!(trimmed.graph.nodes().len(), 4);
+        for word in ["ab", "a", "abb", "aa", ""].iter() {
+            assert_eq!(trimmed.contains(word.chars()), automaton.contains(word.chars()), "{}", word);
+        }
+
+        let empty = Nfa::from_edges(vec![
+            (0, Some('a'), 1),
+        ], Vec::<usize>::new());
+        let (trimmed, renumbering) = empty.trim();
+        assert_eq!(renumbering, vec![Some(0), None]);
+        assert!(!trimmed.contains("a".chars()));
+    }
+
+    #[test]
+    fn convert_to_regex() {
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('0'), 0),
+            (0, None, 1),
+            (0, Some('1'), 1),
+            (1, Some('0'), 0),
+        ], vec![1]);
+
+        let regex = automaton.to_regex();
+        let alphabet = ['0', '1'];
+        assert_eq!(
+            regex.to_dfa(alphabet.iter().cloned()).minimized(),
+            automaton.into_dfa(alphabet.iter().cloned()).minimized());
+    }
+
+    #[test]
+    fn elimination_orders() {
+        // A hub state in the middle of two chains, with a loop through all of them.
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('a'), 1),
+            (1, Some('b'), 2),
+            (2, Some('c'), 3),
+            (3, Some('a'), 4),
+            (4, Some('b'), 2),
+            (2, Some('a'), 0),
+            (3, Some('c'), 1),
+            (4, None, 0),
+        ], vec![3]);
+
+        let orders = [
+            EliminationOrder::Reverse,
+            EliminationOrder::MinDegree,
+            EliminationOrder::MinWeight,
+        ];
+
+        let regexes = orders.iter().map(|&order| automaton.to_regex_with(RegexOptions {
+            order,
+            .. RegexOptions::default()
+        })).collect::<Vec<_>>();
+
+        let alphabet = ['a', 'b', 'c'];
+        let expected = automaton.into_dfa(alphabet.iter().cloned()).minimized();
+        for regex in regexes.iter() {
+            assert_eq!(regex.to_dfa(alphabet.iter().cloned()).minimized(), expected);
+        }
+
+        let printer = regex::Printer::default();
+        assert_eq!(printer.print(&regexes[2]), "ab(c(ab|cb)|(a|ca)ab)*c");
+        assert!(printer.print(&regexes[1]).len() < printer.print(&regexes[0]).len());
+    }
+
+    #[test]
+    fn convert_empty_to_regex() {
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('0'), 0),
+            (1, Some('1'), 2),
+        ], vec![2]);
+
+        let regex = automaton.to_regex();
+        assert_eq!(regex.to_string(), "[]");
+        assert!(!regex.to_nfa().contains("1".chars()));
+
+        let automaton = Nfa::<char>::from_edges(vec![], vec![0]);
+        assert_eq!(automaton.to_regex().to_string(), "{e}");
+    }
 }
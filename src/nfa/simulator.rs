@@ -0,0 +1,308 @@
+//! Simulation of an nfa on all of its active states at once.
+//!
+//! The epsilon closure of a state is computed when it first becomes active and then kept, so that
+//! each step is a union of the closures of all targets. Sets of active states are bitsets.
+//! Optionally, each distinct set is remembered together with its successors, which builds the part
+//! of the powerset automaton used by the input on the fly.
+use std::collections::{BTreeSet, HashMap};
+use std::mem;
+
+use crate::Alphabet;
+use super::{Nfa, Node};
+
+/// Drives an nfa symbol by symbol.
+///
+/// Epsilon closures are computed once per state, the first time it is reached. Reuse a simulator
+/// with `reset` for matching several words, to keep the closures already computed.
+#[derive(Clone)]
+pub struct Simulator<'a, A: Alphabet> {
+    automaton: &'a Nfa<A>,
+
+    /// The states reachable from each state by epsilon transitions, including itself.
+    ///
+    /// Only filled for states which have been active.
+    closures: Vec<Option<BitSet>>,
+
+    /// The final states.
+    finals: BitSet,
+
+    /// The active states.
+    current: BitSet,
+
+    /// Memoized sets of states and their transitions, if enabled.
+    cache: Option<LazyDfa<A>>,
+}
+
+/// A set of dense indices.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+/// The part of the powerset automaton explored so far.
+#[derive(Clone, Debug)]
+struct LazyDfa<A: Alphabet> {
+    /// All seen sets of states.
+    states: Vec<BitSet>,
+
+    /// The index of each seen set.
+    ids: HashMap<BitSet, usize>,
+
+    /// The successor of a set with a symbol.
+    transitions: HashMap<(usize, A), usize>,
+
+    /// The index of the active set.
+    current: usize,
+
+    /// The number of sets after which the cache is cleared.
+    limit: usize,
+}
+
+impl<'a, A: Alphabet> Simulator<'a, A> {
+    /// Create a simulator in the start state of the automaton.
+    pub fn new(automaton: &'a Nfa<A>) -> Self {
+        let count = automaton.graph.nodes().len();
+        let finals = BitSet::with_elements(count, automaton.finals.iter().cloned());
+
+        let mut simulator = Simulator {
+            automaton,
+            closures: vec![None; count],
+            finals,
+            current: BitSet::new(count),
+            cache: None,
+        };
+        simulator.current = simulator.closure(0).clone();
+        simulator
+    }
+
+    /// Memoize the sets of active states and their transitions.
+    ///
+    /// Repeated steps from a set of states with the same symbol are then a single lookup. When
+    /// more than `limit` sets have been stored, the cache is cleared to bound its memory.
+    pub fn cache(mut self, limit: usize) -> Self {
+        let mut cache = LazyDfa {
+            states: Vec::new(),
+            ids: HashMap::new(),
+            transitions: HashMap::new(),
+            current: 0,
+            limit: limit.max(1),
+        };
+        cache.current = cache.intern(&self.current).0;
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Advance by one symbol of the input.
+    pub fn step(&mut self, symbol: A) {
+        let cached = self.cache.as_ref()
+            .and_then(|cache| cache.transitions.get(&(cache.current, symbol)).cloned());
+        if let Some(next) = cached {
+            let cache = self.cache.as_mut().unwrap();
+            self.current.clone_from(&cache.states[next]);
+            cache.current = next;
+            return;
+        }
+
+        let automaton = self.automaton;
+        let current = mem::replace(&mut self.current, BitSet::new(0));
+        let mut next = BitSet::new(self.closures.len());
+        for state in current.iter() {
+            let mut edges = automaton.graph.edges(state).unwrap();
+            edges.restrict_to(&Some(symbol));
+            for target in edges.targets() {
+                next.union_with(self.closure(target));
+            }
+        }
+
+        if let Some(cache) = self.cache.as_mut() {
+            let from = cache.current;
+            let (to, cleared) = cache.intern(&next);
+            // A cleared cache no longer contains the previous set.
+            if !cleared {
+                cache.transitions.insert((from, symbol), to);
+            }
+            cache.current = to;
+        }
+
+        self.current = next;
+    }
+
+    /// Check whether the input so far is a word of the language.
+    pub fn is_accepting(&self) -> bool {
+        self.current.intersects(&self.finals)
+    }
+
+    /// Check whether no state is active anymore.
+    ///
+    /// No continuation of the input is then a word of the language. The converse need not hold,
+    /// since active states need not reach a final state.
+    pub fn is_dead(&self) -> bool {
+        self.current.is_empty()
+    }
+
+    /// Return to the start state, as if no input had been consumed.
+    pub fn reset(&mut self) {
+        let start = self.closure(0).clone();
+        self.current = start;
+        if let Some(cache) = self.cache.as_mut() {
+            cache.current = cache.intern(&self.current).0;
+        }
+    }
+
+    /// The currently active states, in ascending order.
+    pub fn states(&self) -> impl Iterator<Item=Node> + '_ {
+        self.current.iter().map(Node)
+    }
+
+    /// The number of sets of states in the cache.
+    pub fn cached_states(&self) -> usize {
+        self.cache.as_ref().map_or(0, |cache| cache.states.len())
+    }
+
+    /// The epsilon closure of a state, computing it on first use.
+    fn closure(&mut self, state: usize) -> &BitSet {
+        let automaton = self.automaton;
+        let count = self.closures.len();
+        self.closures[state].get_or_insert_with(|| {
+            BitSet::with_elements(count, automaton.epsilon_reach::<BTreeSet<_>>(Node(state)))
+        })
+    }
+}
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        BitSet {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    fn with_elements<I: IntoIterator<Item=Node>>(len: usize, iter: I) -> Self {
+        let mut set = BitSet::new(len);
+        for Node(idx) in iter {
+            set.words[idx / 64] |= 1 << (idx % 64);
+        }
+        set
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other;
+        }
+    }
+
+    fn intersects(&self, other: &Self) -> bool {
+        self.words.iter().zip(other.words.iter()).any(|(a, b)| a & b != 0)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    fn iter(&self) -> impl Iterator<Item=usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(idx, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| idx*64 + bit)
+        })
+    }
+}
+
+impl<A: Alphabet> LazyDfa<A> {
+    /// The index of a set, storing it if it is new.
+    ///
+    /// Also returns whether the cache was cleared to make room for the set.
+    fn intern(&mut self, set: &BitSet) -> (usize, bool) {
+        if let Some(&id) = self.ids.get(set) {
+            return (id, false);
+        }
+
+        let cleared = self.states.len() >= self.limit;
+        if cleared {
+            self.states.clear();
+            self.ids.clear();
+            self.transitions.clear();
+        }
+
+        let id = self.states.len();
+        self.states.push(set.clone());
+        self.ids.insert(set.clone(), id);
+        (id, cleared)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simulate() {
+        // Words with a `1` at the third position from the end.
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('0'), 0),
+            (0, Some('1'), 0),
+            (0, None, 1),
+            (1, Some('1'), 2),
+            (2, Some('0'), 3),
+            (2, Some('1'), 3),
+            (3, Some('0'), 4),
+            (3, Some('1'), 4),
+        ], vec![4]);
+
+        let mut simulators = [Simulator::new(&automaton), Simulator::new(&automaton).cache(16)];
+        for simulator in simulators.iter_mut() {
+            let words = [
+                ("100", true),
+                ("0111", true),
+                ("1011", false),
+                ("", false),
+                ("11", false),
+                ("2100", false),
+            ];
+
+            for &(word, expected) in words.iter() {
+                simulator.reset();
+                word.chars().for_each(|ch| simulator.step(ch));
+                assert_eq!(simulator.is_accepting(), expected, "{}", word);
+            }
+
+            simulator.reset();
+            assert_eq!(simulator.states().collect::<Vec<_>>(), vec![Node(0), Node(1)]);
+            simulator.step('2');
+            assert!(simulator.is_dead());
+        }
+    }
+
+    #[test]
+    fn lazy_closures() {
+        let chain = (0..100).map(|state| (state, Some('a'), state + 1));
+        let automaton = Nfa::from_edges(chain, vec![100]);
+
+        let mut simulator = Simulator::new(&automaton);
+        simulator.step('a');
+        simulator.step('a');
+        assert_eq!(simulator.closures.iter().filter(|closure| closure.is_some()).count(), 3);
+        assert_eq!(simulator.states().collect::<Vec<_>>(), vec![Node(2)]);
+    }
+
+    #[test]
+    fn cache_limit() {
+        let automaton = Nfa::from_edges(vec![
+            (0, Some('a'), 0),
+            (0, Some('a'), 1),
+            (1, Some('a'), 2),
+            (2, Some('a'), 3),
+        ], vec![3]);
+
+        let mut simulator = Simulator::new(&automaton).cache(2);
+        for _ in 0..3 {
+            simulator.step('a');
+            assert!(simulator.cached_states() <= 2);
+        }
+        assert!(simulator.is_accepting());
+
+        simulator.reset();
+        simulator.step('a');
+        simulator.step('a');
+        assert!(!simulator.is_accepting());
+    }
+}
@@ -69,7 +69,7 @@ impl<A: Alphabet> NonDeterministic<A> {
         Builder::default()
     }
 
-    pub fn edges(&self, node: usize) -> Option<Edges<A>> {
+    pub fn edges(&self, node: usize) -> Option<Edges<'_, A>> {
         let range = self.ranges.get(node)?;
         Some(Edges {
             graph: self,
@@ -77,7 +77,7 @@ impl<A: Alphabet> NonDeterministic<A> {
         })
     }
 
-    pub fn nodes(&self) -> Nodes<A> {
+    pub fn nodes(&self) -> Nodes<'_, A> {
         Nodes { 
             node_id: 0,
             graph: self
@@ -131,6 +131,24 @@ impl<A: Alphabet> NonDeterministic<A> {
         }
     }
 
+    /// A builder for the graph with the direction of all edges reversed.
+    ///
+    /// All nodes are shifted by `offset`, which leaves room for new nodes in front of them.
+    pub fn reversed(&self, offset: usize) -> Builder<A> {
+        let mut builder = Builder::default();
+        if let Some(last) = (offset + self.ranges.len()).checked_sub(1) {
+            builder.ensure_nodes(last);
+        }
+
+        for (from, edges) in self.nodes() {
+            for (symbol, to) in edges {
+                builder.insert(to + offset, symbol, from + offset);
+            }
+        }
+
+        builder
+    }
+
     fn label(&self, ch: &A) -> Option<Label> {
         self.characters
             .binary_search(ch)
@@ -210,7 +228,8 @@ impl<A: Alphabet> Builder<A> {
         })
     }
 
-    fn ensure_nodes(&mut self, node: usize) {
+    /// Ensure that all nodes up to and including `node` exist, even without edges.
+    pub fn ensure_nodes(&mut self, node: usize) {
         self.edges.ensure_with(node + 1, Vec::new);
     }
 }
@@ -244,10 +263,10 @@ impl<'a, A: Alphabet> Edges<'a, A> {
         };
         let begin = self.edges.iter()
             .position(|edge| edge.label >= label)
-            .unwrap_or_else(|| self.edges.len());
+            .unwrap_or(self.edges.len());
         let end = self.edges.iter()
             .position(|edge| edge.label > label)
-            .unwrap_or_else(|| self.edges.len());
+            .unwrap_or(self.edges.len());
         self.edges = &self.edges[begin..end];
     }
 
@@ -258,7 +277,7 @@ impl<'a, A: Alphabet> Edges<'a, A> {
 
 impl<'a, A: Alphabet> Nodes<'a, A> {
     fn todo(&self) -> usize {
-        let len = self.graph.edges.len();
+        let len = self.graph.ranges.len();
         len - self.node_id
     }
 }
@@ -0,0 +1,149 @@
+//! A refinable partition of a set of dense indices.
+//!
+//! Elements of each block are stored contiguously in a single permutation of all elements. To
+//! split a block, the elements of a splitter set are marked by moving them to the front of their
+//! block, the marked prefix then becomes a new block. Each split costs time proportional to the
+//! size of the splitter, which is what makes Hopcroft's algorithm run in `O(n·log n)`.
+use std::ops::Range;
+
+pub struct Partition {
+    /// All elements, grouped by their block.
+    elements: Vec<usize>,
+
+    /// The position of each element within `elements`.
+    location: Vec<usize>,
+
+    /// The block of each element.
+    block_of: Vec<usize>,
+
+    /// Range of each block within `elements`.
+    blocks: Vec<Range<usize>>,
+
+    /// Number of marked elements at the start of each block.
+    marked: Vec<usize>,
+
+    /// Blocks with at least one marked element.
+    touched: Vec<usize>,
+}
+
+impl Partition {
+    /// Create a partition with a single block, containing all of `0..len`.
+    ///
+    /// When `len` is zero, the partition has no blocks at all.
+    pub fn new(len: usize) -> Self {
+        let blocks: Vec<Range<usize>> = Some(0..len)
+            .filter(|all| !all.is_empty())
+            .into_iter()
+            .collect();
+        let marked = vec![0; blocks.len()];
+        Partition {
+            elements: (0..len).collect(),
+            location: (0..len).collect(),
+            block_of: vec![0; len],
+            blocks,
+            marked,
+            touched: vec![],
+        }
+    }
+
+    /// The number of blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// The block containing an element.
+    pub fn block_of(&self, element: usize) -> usize {
+        self.block_of[element]
+    }
+
+    /// All elements of a block, in no particular order.
+    pub fn block(&self, block: usize) -> &[usize] {
+        &self.elements[self.blocks[block].clone()]
+    }
+
+    /// Mark an element for the next call to `split`.
+    ///
+    /// Marking an element twice has no additional effect.
+    pub fn mark(&mut self, element: usize) {
+        let block = self.block_of[element];
+        let range = self.blocks[block].clone();
+        let first_unmarked = range.start + self.marked[block];
+        let location = self.location[element];
+
+        if location < first_unmarked {
+            return;
+        }
+
+        let other = self.elements[first_unmarked];
+        self.elements.swap(location, first_unmarked);
+        self.location[other] = location;
+        self.location[element] = first_unmarked;
+
+        if self.marked[block] == 0 {
+            self.touched.push(block);
+        }
+        self.marked[block] += 1;
+    }
+
+    /// Split all blocks into their marked and unmarked elements.
+    ///
+    /// Calls `on_split(old, new)` for each block that was split. The marked elements form the
+    /// block `new` and the remaining elements stay in `old`. Afterwards, no element is marked.
+    pub fn split<F>(&mut self, mut on_split: F)
+        where F: FnMut(usize, usize)
+    {
+        while let Some(block) = self.touched.pop() {
+            let marked = self.marked[block];
+            self.marked[block] = 0;
+
+            let range = self.blocks[block].clone();
+            if marked == range.len() {
+                continue;
+            }
+
+            let new_block = self.blocks.len();
+            let middle = range.start + marked;
+            self.blocks[block] = middle..range.end;
+            self.blocks.push(range.start..middle);
+            self.marked.push(0);
+
+            for &element in &self.elements[range.start..middle] {
+                self.block_of[element] = new_block;
+            }
+
+            on_split(block, new_block);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_marked() {
+        let mut partition = Partition::new(5);
+        assert_eq!(partition.len(), 1);
+
+        partition.mark(3);
+        partition.mark(1);
+        partition.mark(3);
+
+        let mut splits = vec![];
+        partition.split(|old, new| splits.push((old, new)));
+        assert_eq!(splits, vec![(0, 1)]);
+        assert_eq!(partition.len(), 2);
+
+        let mut marked = partition.block(1).to_vec();
+        marked.sort();
+        assert_eq!(marked, vec![1, 3]);
+        assert_eq!(partition.block_of(0), 0);
+        assert_eq!(partition.block_of(3), 1);
+
+        // Marking a whole block does not split it.
+        partition.mark(1);
+        partition.mark(3);
+        partition.split(|_, _| panic!("Block should not be split"));
+        assert_eq!(partition.len(), 2);
+    }
+}
@@ -1,8 +1,16 @@
-use std::collections::HashMap;
-use std::fmt::Write;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::{self, Write};
 
-use super::Alphabet;
-// use super::nfa::Nfa;
+use super::{Alphabet, Error};
+use super::dfa::Dfa;
+use super::nfa::{self, Nfa};
+use super::nondeterministic::{Builder, NonDeterministic};
+
+mod parse;
+mod print;
+
+pub use self::parse::{ParseError, ParseErrorKind};
+pub use self::print::{Printer, Syntax};
 
 /// Represents regular expressions over some finite alphabet.
 ///
@@ -10,15 +18,18 @@ use super::Alphabet;
 /// polynomial time conversion from NFA. You can create (and keep) handles on 
 /// subexpressions, then evaluate as if those subexpressions were at the root.
 /// This relationship forms an acyclic graph.
+#[derive(Clone)]
 pub struct Regex<A: Alphabet> {
     subs: Vec<Op<A>>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub struct Handle(usize);
+pub struct Handle(pub(crate) usize);
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Op<A: Alphabet> {
+    /// The empty language, matching no word at all.
+    Empty,
     Epsilon,
     Match(A),
     Star(Handle),
@@ -26,10 +37,33 @@ pub enum Op<A: Alphabet> {
     Concat(Handle, Handle),
 }
 
+/// Position sets of a subexpression for the Glushkov construction.
+struct Positions {
+    /// Whether the subexpression matches the empty word.
+    nullable: bool,
+
+    /// Positions which can match the first symbol of a word.
+    first: Vec<usize>,
+
+    /// Positions which can match the last symbol of a word.
+    last: Vec<usize>,
+}
+
+/// Computes Brzozowski derivatives of subexpressions.
+///
+/// Derivative terms are identified through the cache. They are constructed with simplification,
+/// which normalizes all alternatives to a sorted chain without duplicates such that similar terms
+/// receive the same handle.
+struct Derivatives<A: Alphabet> {
+    cached: Cached<A>,
+    derivatives: HashMap<(Handle, A), Handle>,
+}
+
 /// Provides access to creating new regex expressions with cached results.
 pub struct Cached<A: Alphabet> {
     regex: Regex<A>,
     cache: HashMap<Op<A>, Handle>,
+    nullable: HashMap<Handle, bool>,
 }
 
 impl<A: Alphabet> Regex<A> {
@@ -39,41 +73,156 @@ impl<A: Alphabet> Regex<A> {
         }
     }
 
-    /*
-    /// Convert the regex into an nfa (NOT YET IMPLEMENTED).
+    /// Convert the regex into an equivalent nfa.
     ///
-    /// Idea:
+    /// Uses the Thompson construction, such that the automaton is linear in the size of the
+    /// expression tree. Every occurrence of a shared subexpression is expanded separately, a regex
+    /// with many reused handles can thus result in an automaton much larger than the regex itself.
+    /// State 0 is the start and state 1 the only final state.
+    pub fn to_nfa(&self) -> Nfa<A> {
+        let mut builder = NonDeterministic::builder();
+        builder.ensure_nodes(1);
+        let mut next = 2;
+
+        if let Some(root) = self.root() {
+            self.thompson(root, 0, 1, &mut builder, &mut next);
+        }
+
+        let finals = vec![nfa::Node(1)].into_iter().collect();
+        Nfa::from_nondeterministic(builder.finish(), finals)
+    }
+
+    /// Convert the regex into an nfa.
     ///
-    /// Is like a regex-labeled nfa with only one final state.
+    /// See `to_nfa` for details.
     pub fn into_nfa(self) -> Nfa<A> {
-        unimplemented!()
+        self.to_nfa()
+    }
+
+    /// Convert the regex into an equivalent nfa without epsilon transitions.
+    ///
+    /// Uses the Glushkov construction where each occurrence of a `Match` is a position of the
+    /// regex. The automaton has exactly one state per position, numbered in order of occurrence
+    /// from 1, and the start state 0. The edges into a state are all labeled with the symbol of
+    /// its position. As with `to_nfa`, every occurrence of a shared subexpression is counted
+    /// separately.
+    pub fn to_position_nfa(&self) -> Nfa<A> {
+        let mut symbols = Vec::new();
+        let mut follow = Vec::new();
+        let mut builder = NonDeterministic::builder();
+        let mut finals = HashSet::new();
+
+        let positions = match self.root() {
+            Some(root) => self.glushkov(root, &mut symbols, &mut follow),
+            None => Positions { nullable: false, first: vec![], last: vec![] },
+        };
+
+        builder.ensure_nodes(symbols.len());
+        for &first in positions.first.iter() {
+            builder.insert(0, &Some(symbols[first]), first + 1);
+        }
+
+        for (from, mut next) in follow.into_iter().enumerate() {
+            next.sort();
+            next.dedup();
+            for to in next {
+                builder.insert(from + 1, &Some(symbols[to]), to + 1);
+            }
+        }
+
+        if positions.nullable {
+            finals.insert(nfa::Node(0));
+        }
+        finals.extend(positions.last.iter().map(|&last| nfa::Node(last + 1)));
+
+        Nfa::from_nondeterministic(builder.finish(), finals)
+    }
+
+    /// Convert the regex directly into an equivalent dfa.
+    ///
+    /// The states of the automaton are the Brzozowski derivatives of the regex, which avoids the
+    /// intermediate nfa of the Thompson construction and its powerset. Derivatives are identified
+    /// up to associativity, commutativity and idempotence of alternatives, of which there are only
+    /// finitely many. All symbols matched by the regex are part of the alphabet, `alphabet`
+    /// provides a way to indicate additional symbols.
+    pub fn to_dfa<I: IntoIterator<Item=A>>(&self, alphabet: I) -> Dfa<A> {
+        let alphabet = self.subs.iter()
+            .filter_map(|op| match op {
+                Op::Match(a) => Some(*a),
+                _ => None,
+            })
+            .chain(alphabet)
+            .collect::<BTreeSet<_>>();
+
+        let mut derivatives = Derivatives::new(self.clone());
+        let start = derivatives.root();
+        let mut state_map = vec![(start, 0)].into_iter().collect::<HashMap<_, _>>();
+        let mut pending = vec![start].into_iter().collect::<VecDeque<_>>();
+        let mut edges = Vec::new();
+        let mut finals = Vec::new();
+
+        while let Some(term) = pending.pop_front() {
+            let from = state_map[&term];
+            if derivatives.cached.nullable(term) {
+                finals.push(from);
+            }
+
+            for &ch in alphabet.iter() {
+                let derived = derivatives.derive(term, ch);
+                let new_index = state_map.len();
+                let to = *state_map.entry(derived).or_insert_with(|| {
+                    pending.push_back(derived);
+                    new_index
+                });
+                edges.push((from, ch, to));
+            }
+        }
+
+        Dfa::from_edges(edges, finals)
     }
-    */
 
     /// Push a new operation as the regex root.
     ///
     /// It is not required that all regex states are reachable afterwards but all
     /// handles must point to existing operations. Returns a handle on the newly
     /// inserted operation.
+    ///
+    /// # Panics
+    /// When the operation refers to a handle which does not exist.
     pub fn push(&mut self, op: Op<A>) -> Handle {
-        match op {
-            Op::Epsilon => (),
-            Op::Match(_) => (),
-            Op::Star(Handle(i)) => assert!(i < self.subs.len()),
-            Op::Or(Handle(i), Handle(j)) => assert!(i < self.subs.len() && j < self.subs.len()),
-            Op::Concat(Handle(i), Handle(j)) => assert!(i < self.subs.len() && j < self.subs.len()),
+        self.try_push(op).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Push a new operation as the regex root, if all its handles point to existing operations.
+    pub fn try_push(&mut self, op: Op<A>) -> Result<Handle, Error<A>> {
+        let len = self.subs.len();
+        let invalid = match op {
+            Op::Empty | Op::Epsilon | Op::Match(_) => None,
+            Op::Star(sub) => Some(sub).filter(|&Handle(i)| i >= len),
+            Op::Or(a, b) | Op::Concat(a, b) => [a, b].iter().cloned().find(|&Handle(i)| i >= len),
+        };
+
+        if let Some(handle) = invalid {
+            return Err(Error::InvalidHandle(handle));
         }
 
         let handle = Handle(self.subs.len());
         self.subs.push(op);
-        handle
+        Ok(handle)
     }
 
     /// Get a root to the regex.
+    ///
+    /// A regex without any operations has no root, it is treated as the empty language.
     pub fn root(&self) -> Option<Handle> {
         self.subs.len().checked_sub(1).map(Handle)
     }
 
+    /// The operation of a subexpression.
+    pub(crate) fn op(&self, Handle(handle): Handle) -> &Op<A> {
+        &self.subs[handle]
+    }
+
     /// Modify the regex with a cache for same terms.
     ///
     /// By using a cache for terms, it is possible to lower the memory requirements of the
@@ -82,40 +231,230 @@ impl<A: Alphabet> Regex<A> {
         Cached {
             regex: self,
             cache: HashMap::new(),
+            nullable: HashMap::new(),
+        }
+    }
+
+    /// Get an equivalent regex with simplified subexpressions.
+    ///
+    /// Rebuilds the regex bottom-up with `Cached::insert_simplified`, which removes redundant
+    /// epsilons, stars and alternatives by identities of Kleene algebra. The result only contains
+    /// the subexpressions of the simplified root.
+    pub fn simplify(&self) -> Regex<A> {
+        let root = match self.root() {
+            Some(root) => root,
+            None => return Regex::new(),
+        };
+
+        let mut cached = Cached::new();
+        let mut simplified = Vec::with_capacity(self.subs.len());
+        // Operations only refer to earlier ones, so a single pass suffices.
+        for op in self.subs.iter() {
+            let op = op.map_handles(|Handle(sub)| simplified[sub]);
+            simplified.push(cached.insert_simplified(op));
+        }
+
+        cached.into_inner().subexpression(simplified[root.0])
+    }
+
+    /// Extract a subexpression as a standalone regex with it as the root.
+    ///
+    /// Only the operations reachable from the handle are retained.
+    pub fn subexpression(&self, Handle(root): Handle) -> Regex<A> {
+        let mut reachable = vec![false; root + 1];
+        reachable[root] = true;
+        for idx in (0..=root).rev() {
+            if reachable[idx] {
+                self.subs[idx].map_handles(|Handle(sub)| {
+                    reachable[sub] = true;
+                    Handle(sub)
+                });
+            }
+        }
+
+        let mut regex = Regex::new();
+        let mut moved = vec![None; root + 1];
+        for idx in (0..=root).filter(|&idx| reachable[idx]) {
+            let op = self.subs[idx].map_handles(|Handle(sub)| moved[sub].unwrap());
+            moved[idx] = Some(regex.push(op));
+        }
+
+        regex
+    }
+
+    /// Insert states and edges matching the subexpression between two existing nodes.
+    ///
+    /// New nodes are allocated by incrementing `next`. Only the edges created for a starred
+    /// subexpression start and end at the same node, and that node is always a fresh one. Hence,
+    /// the fragments of an alternative can share their end points without creating new paths.
+    pub(crate) fn thompson(
+        &self,
+        Handle(handle): Handle,
+        from: usize,
+        to: usize,
+        builder: &mut Builder<Option<A>>,
+        next: &mut usize,
+    ) {
+        let mut fresh = || {
+            let node = *next;
+            *next += 1;
+            node
+        };
+
+        match self.subs[handle] {
+            Op::Empty => (),
+            Op::Epsilon => builder.insert(from, &None, to),
+            Op::Match(a) => builder.insert(from, &Some(a), to),
+            Op::Star(sub) => {
+                let repeat = fresh();
+                builder.insert(from, &None, repeat);
+                builder.insert(repeat, &None, to);
+                self.thompson(sub, repeat, repeat, builder, next);
+            },
+            Op::Or(a, b) => {
+                self.thompson(a, from, to, builder, next);
+                self.thompson(b, from, to, builder, next);
+            },
+            Op::Concat(a, b) => {
+                let middle = fresh();
+                self.thompson(a, from, middle, builder, next);
+                self.thompson(b, middle, to, builder, next);
+            },
         }
     }
 
-    /// Get a string representation of this regex.
-    pub fn to_string(&self) -> String {
-        let mut string = String::new();
-        self.push_from_root(self.root().unwrap(), &mut string);
-        string
+    /// Compute the first and last positions of a subexpression, recording the follow sets.
+    ///
+    /// Every `Match` visited is appended as a new position to `symbols` and `follow`.
+    fn glushkov(
+        &self,
+        Handle(handle): Handle,
+        symbols: &mut Vec<A>,
+        follow: &mut Vec<Vec<usize>>,
+    ) -> Positions {
+        match self.subs[handle] {
+            Op::Empty => Positions { nullable: false, first: vec![], last: vec![] },
+            Op::Epsilon => Positions { nullable: true, first: vec![], last: vec![] },
+            Op::Match(a) => {
+                let position = symbols.len();
+                symbols.push(a);
+                follow.push(vec![]);
+                Positions { nullable: false, first: vec![position], last: vec![position] }
+            },
+            Op::Star(sub) => {
+                let sub = self.glushkov(sub, symbols, follow);
+                for &last in sub.last.iter() {
+                    follow[last].extend(sub.first.iter().cloned());
+                }
+                Positions { nullable: true, .. sub }
+            },
+            Op::Or(a, b) => {
+                let mut a = self.glushkov(a, symbols, follow);
+                let b = self.glushkov(b, symbols, follow);
+                a.first.extend(b.first);
+                a.last.extend(b.last);
+                Positions { nullable: a.nullable || b.nullable, .. a }
+            },
+            Op::Concat(a, b) => {
+                let mut a = self.glushkov(a, symbols, follow);
+                let mut b = self.glushkov(b, symbols, follow);
+                for &last in a.last.iter() {
+                    follow[last].extend(b.first.iter().cloned());
+                }
+                if a.nullable {
+                    a.first.extend(b.first.iter().cloned());
+                }
+                if b.nullable {
+                    b.last.extend(a.last.iter().cloned());
+                }
+                Positions {
+                    nullable: a.nullable && b.nullable,
+                    first: a.first,
+                    last: b.last,
+                }
+            },
+        }
     }
 
-    fn push_from_root(&self, Handle(root): Handle, string: &mut String) {
+    fn write_from_root<W: Write>(&self, Handle(root): Handle, out: &mut W) -> fmt::Result {
         match self.subs[root] {
-            Op::Epsilon => string.push_str("{e}"),
-            Op::Match(a) => write!(string, "{{{:?}}}", a).unwrap(),
+            Op::Empty => out.write_str("[]"),
+            Op::Epsilon => out.write_str("{e}"),
+            Op::Match(a) => write!(out, "{{{:?}}}", a),
             Op::Star(sub) => {
-                string.push('(');
-                self.push_from_root(sub, string);
-                string.push_str(")*");
+                out.write_char('(')?;
+                self.write_from_root(sub, out)?;
+                out.write_str(")*")
             },
             Op::Or(a, b) => {
-                string.push('(');
-                self.push_from_root(a, string);
-                string.push('|');
-                self.push_from_root(b, string);
-                string.push(')');
+                out.write_char('(')?;
+                self.write_from_root(a, out)?;
+                out.write_char('|')?;
+                self.write_from_root(b, out)?;
+                out.write_char(')')
             },
             Op::Concat(a, b) => {
-                self.push_from_root(a, string);
-                self.push_from_root(b, string);
+                self.write_from_root(a, out)?;
+                self.write_from_root(b, out)
             },
         }
     }
 }
 
+impl<A: Alphabet> Derivatives<A> {
+    fn new(regex: Regex<A>) -> Self {
+        let mut cached = regex.cached();
+        cached.fill_cache();
+        Derivatives {
+            cached,
+            derivatives: HashMap::new(),
+        }
+    }
+
+    /// The root of the regex, or the empty language.
+    fn root(&mut self) -> Handle {
+        match self.cached.inner().root() {
+            Some(root) => root,
+            None => self.cached.insert(Op::Empty),
+        }
+    }
+
+    /// The derivative of the term with respect to a symbol.
+    fn derive(&mut self, handle: Handle, ch: A) -> Handle {
+        if let Some(&derived) = self.derivatives.get(&(handle, ch)) {
+            return derived;
+        }
+
+        let derived = match self.cached.inner().subs[handle.0] {
+            Op::Empty | Op::Epsilon => self.cached.insert(Op::Empty),
+            Op::Match(a) if a == ch => self.cached.insert(Op::Epsilon),
+            Op::Match(_) => self.cached.insert(Op::Empty),
+            Op::Star(sub) => {
+                let derived = self.derive(sub, ch);
+                self.cached.insert_simplified(Op::Concat(derived, handle))
+            },
+            Op::Or(a, b) => {
+                let left = self.derive(a, ch);
+                let right = self.derive(b, ch);
+                self.cached.insert_simplified(Op::Or(left, right))
+            },
+            Op::Concat(a, b) => {
+                let left = self.derive(a, ch);
+                let left = self.cached.insert_simplified(Op::Concat(left, b));
+                if self.cached.nullable(a) {
+                    let right = self.derive(b, ch);
+                    self.cached.insert_simplified(Op::Or(left, right))
+                } else {
+                    left
+                }
+            },
+        };
+
+        self.derivatives.insert((handle, ch), derived);
+        derived
+    }
+}
+
 impl<A: Alphabet> Cached<A> {
     pub fn new() -> Self {
         Regex::new().cached()
@@ -132,6 +471,143 @@ impl<A: Alphabet> Cached<A> {
         *value
     }
 
+    /// Insert a new operation, simplified by identities of Kleene algebra.
+    ///
+    /// The result is equivalent to the operation but may be a smaller, already existing term:
+    ///
+    /// * `∅x = x∅ = ∅`, `εx = xε = x` and `x*x* = x*`.
+    /// * Alternatives are associative, commutative and idempotent, `∅` is neutral for them.
+    /// * `ε|x = x` when `x` matches the empty word, and `x|x* = x*`.
+    /// * `∅* = ε* = ε`, `x** = x*`, `(xx*)* = x*` and `(ε|x|y*)* = (x|y)*`.
+    ///
+    /// The rules only look at the operands themselves, not deeper. Hence, simplification is most
+    /// effective when all subexpressions are inserted through this method as well.
+    pub fn insert_simplified(&mut self, op: Op<A>) -> Handle {
+        match op {
+            Op::Star(sub) => self.simplify_star(sub),
+            Op::Or(a, b) => {
+                let mut alternatives = Vec::new();
+                self.alternatives(a, &mut alternatives);
+                self.alternatives(b, &mut alternatives);
+                self.simplify_or(alternatives)
+            },
+            Op::Concat(a, b) => self.simplify_concat(a, b),
+            op => self.insert(op),
+        }
+    }
+
+    /// Check if the subexpression matches the empty word.
+    pub fn nullable(&mut self, handle: Handle) -> bool {
+        if let Some(&nullable) = self.nullable.get(&handle) {
+            return nullable;
+        }
+
+        let nullable = match self.op(handle) {
+            Op::Empty => false,
+            Op::Epsilon => true,
+            Op::Match(_) => false,
+            Op::Star(_) => true,
+            Op::Or(a, b) => self.nullable(a) || self.nullable(b),
+            Op::Concat(a, b) => self.nullable(a) && self.nullable(b),
+        };
+
+        self.nullable.insert(handle, nullable);
+        nullable
+    }
+
+    fn op(&self, Handle(handle): Handle) -> Op<A> {
+        self.regex.subs[handle]
+    }
+
+    fn simplify_star(&mut self, sub: Handle) -> Handle {
+        match self.op(sub) {
+            Op::Empty | Op::Epsilon => self.insert(Op::Epsilon),
+            Op::Star(_) => sub,
+            Op::Concat(a, b) if self.op(b) == Op::Star(a) => b,
+            Op::Concat(a, b) if self.op(a) == Op::Star(b) => a,
+            Op::Or(_, _) => {
+                let mut alternatives = Vec::new();
+                self.alternatives(sub, &mut alternatives);
+
+                // Under the star, the empty word and inner stars are redundant.
+                let mut changed = false;
+                for alternative in alternatives.iter_mut() {
+                    match self.op(*alternative) {
+                        Op::Epsilon => changed = true,
+                        Op::Star(inner) => {
+                            *alternative = inner;
+                            changed = true;
+                        },
+                        _ => (),
+                    }
+                }
+
+                if changed {
+                    alternatives.retain(|&alternative| self.op(alternative) != Op::Epsilon);
+                    let inner = self.simplify_or(alternatives);
+                    self.simplify_star(inner)
+                } else {
+                    self.insert(Op::Star(sub))
+                }
+            },
+            _ => self.insert(Op::Star(sub)),
+        }
+    }
+
+    fn simplify_concat(&mut self, first: Handle, second: Handle) -> Handle {
+        match (self.op(first), self.op(second)) {
+            (Op::Empty, _) => first,
+            (_, Op::Empty) => second,
+            (Op::Epsilon, _) => second,
+            (_, Op::Epsilon) => first,
+            (Op::Star(_), Op::Star(_)) if first == second => first,
+            _ => self.insert(Op::Concat(first, second)),
+        }
+    }
+
+    fn simplify_or(&mut self, mut alternatives: Vec<Handle>) -> Handle {
+        alternatives.retain(|&alternative| self.op(alternative) != Op::Empty);
+        alternatives.sort_by_key(|handle| handle.0);
+        alternatives.dedup();
+
+        let stars = alternatives.iter()
+            .filter_map(|&alternative| match self.op(alternative) {
+                Op::Star(inner) => Some(inner),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        alternatives.retain(|alternative| !stars.contains(alternative));
+
+        let epsilon = self.insert(Op::Epsilon);
+        if alternatives.len() > 1 && alternatives.contains(&epsilon) {
+            let other_nullable = alternatives.iter()
+                .filter(|&&alternative| alternative != epsilon)
+                .any(|&alternative| self.nullable(alternative));
+            if other_nullable {
+                alternatives.retain(|&alternative| alternative != epsilon);
+            }
+        }
+
+        let last = match alternatives.pop() {
+            Some(last) => last,
+            None => return self.insert(Op::Empty),
+        };
+
+        alternatives.into_iter().rev()
+            .fold(last, |rest, alternative| self.insert(Op::Or(alternative, rest)))
+    }
+
+    /// Collect the non-alternative subterms of nested alternatives.
+    fn alternatives(&self, handle: Handle, into: &mut Vec<Handle>) {
+        match self.op(handle) {
+            Op::Or(a, b) => {
+                self.alternatives(a, into);
+                self.alternatives(b, into);
+            },
+            _ => into.push(handle),
+        }
+    }
+
     /// Access the inner regex.
     pub fn inner(&self) -> &Regex<A> {
         &self.regex
@@ -153,6 +629,32 @@ impl<A: Alphabet> Cached<A> {
     }
 }
 
+impl<A: Alphabet> Op<A> {
+    /// Replace the operands of the operation.
+    fn map_handles<F>(self, mut f: F) -> Self
+        where F: FnMut(Handle) -> Handle
+    {
+        match self {
+            Op::Empty => Op::Empty,
+            Op::Epsilon => Op::Epsilon,
+            Op::Match(a) => Op::Match(a),
+            Op::Star(sub) => Op::Star(f(sub)),
+            Op::Or(a, b) => Op::Or(f(a), f(b)),
+            Op::Concat(a, b) => Op::Concat(f(a), f(b)),
+        }
+    }
+}
+
+/// Get a string representation of this regex.
+impl<A: Alphabet> fmt::Display for Regex<A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.root() {
+            Some(root) => self.write_from_root(root, f),
+            None => f.write_str("[]"),
+        }
+    }
+}
+
 impl<A: Alphabet> Default for Regex<A> {
     fn default() -> Self {
         Self::new()
@@ -164,3 +666,223 @@ impl<A: Alphabet> Default for Cached<A> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_to_nfa() {
+        // (a|b)*abb
+        let mut regex = Regex::new();
+        let a = regex.push(Op::Match('a'));
+        let b = regex.push(Op::Match('b'));
+        let a_or_b = regex.push(Op::Or(a, b));
+        let star = regex.push(Op::Star(a_or_b));
+        let ab = regex.push(Op::Concat(a, b));
+        let abb = regex.push(Op::Concat(ab, b));
+        regex.push(Op::Concat(star, abb));
+
+        let automaton = regex.to_nfa();
+        assert!( automaton.contains("abb".chars()));
+        assert!( automaton.contains("babb".chars()));
+        assert!( automaton.contains("abababb".chars()));
+        assert!(!automaton.contains("".chars()));
+        assert!(!automaton.contains("ab".chars()));
+        assert!(!automaton.contains("abba".chars()));
+
+        let automaton = automaton.into_dfa(vec![]);
+        assert!( automaton.contains("aabb".chars()));
+        assert!(!automaton.contains("bab".chars()));
+    }
+
+    #[test]
+    fn nested_star() {
+        // ((a|e)b*)*
+        let mut regex = Regex::new();
+        let a = regex.push(Op::Match('a'));
+        let e = regex.push(Op::Epsilon);
+        let b = regex.push(Op::Match('b'));
+        let a_or_e = regex.push(Op::Or(a, e));
+        let b_star = regex.push(Op::Star(b));
+        let inner = regex.push(Op::Concat(a_or_e, b_star));
+        regex.push(Op::Star(inner));
+
+        let automaton = regex.into_nfa();
+        assert!( automaton.contains("".chars()));
+        assert!( automaton.contains("bbab".chars()));
+        assert!( automaton.contains("aaa".chars()));
+        assert!(!automaton.contains("c".chars()));
+    }
+
+    #[test]
+    fn convert_to_dfa() {
+        // (a|b)*abb
+        let mut regex = Regex::new();
+        let a = regex.push(Op::Match('a'));
+        let b = regex.push(Op::Match('b'));
+        let a_or_b = regex.push(Op::Or(a, b));
+        let star = regex.push(Op::Star(a_or_b));
+        let ab = regex.push(Op::Concat(a, b));
+        let abb = regex.push(Op::Concat(ab, b));
+        regex.push(Op::Concat(star, abb));
+
+        let automaton = regex.to_dfa(vec!['c']);
+        assert_eq!(automaton.alphabet(), &['a', 'b', 'c']);
+        assert!( automaton.contains("abb".chars()));
+        assert!( automaton.contains("babb".chars()));
+        assert!( automaton.contains("abababb".chars()));
+        assert!(!automaton.contains("".chars()));
+        assert!(!automaton.contains("abba".chars()));
+        assert!(!automaton.contains("cabb".chars()));
+
+        let thompson = regex.to_nfa().into_dfa(vec!['c']);
+        assert_eq!(automaton.minimized(), thompson.minimized());
+    }
+
+    #[test]
+    fn derivatives_terminate() {
+        // (a*|(ab)*)*b, many similar derivatives
+        let mut regex = Regex::new();
+        let a = regex.push(Op::Match('a'));
+        let b = regex.push(Op::Match('b'));
+        let a_star = regex.push(Op::Star(a));
+        let ab = regex.push(Op::Concat(a, b));
+        let ab_star = regex.push(Op::Star(ab));
+        let or = regex.push(Op::Or(a_star, ab_star));
+        let star = regex.push(Op::Star(or));
+        regex.push(Op::Concat(star, b));
+
+        let automaton = regex.to_dfa(vec![]);
+        let thompson = regex.to_nfa().into_dfa(vec![]);
+        assert_eq!(automaton.minimized(), thompson.minimized());
+    }
+
+    #[test]
+    fn convert_to_position_nfa() {
+        // (a|b)*abb
+        let mut regex = Regex::new();
+        let a = regex.push(Op::Match('a'));
+        let b = regex.push(Op::Match('b'));
+        let a_or_b = regex.push(Op::Or(a, b));
+        let star = regex.push(Op::Star(a_or_b));
+        let ab = regex.push(Op::Concat(a, b));
+        let abb = regex.push(Op::Concat(ab, b));
+        regex.push(Op::Concat(star, abb));
+
+        let automaton = regex.to_position_nfa();
+        assert!( automaton.contains("abb".chars()));
+        assert!( automaton.contains("babb".chars()));
+        assert!(!automaton.contains("".chars()));
+        assert!(!automaton.contains("abba".chars()));
+
+        // One state per occurrence of a symbol, and the start.
+        let mut output = Vec::new();
+        automaton.write_to(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains("ε"));
+        assert!( output.contains("5 [peripheries=2,]"));
+        assert!(!output.contains("6"));
+
+        let thompson = regex.to_nfa().into_dfa(vec![]);
+        assert_eq!(automaton.into_dfa(vec![]).minimized(), thompson.minimized());
+    }
+
+    #[test]
+    fn position_nfa_nullable() {
+        // (a|e)b*
+        let mut regex = Regex::new();
+        let a = regex.push(Op::Match('a'));
+        let e = regex.push(Op::Epsilon);
+        let b = regex.push(Op::Match('b'));
+        let a_or_e = regex.push(Op::Or(a, e));
+        let b_star = regex.push(Op::Star(b));
+        regex.push(Op::Concat(a_or_e, b_star));
+
+        let automaton = regex.to_position_nfa();
+        assert!( automaton.contains("".chars()));
+        assert!( automaton.contains("a".chars()));
+        assert!( automaton.contains("bbb".chars()));
+        assert!( automaton.contains("abb".chars()));
+        assert!(!automaton.contains("ba".chars()));
+        assert!(!automaton.contains("aa".chars()));
+    }
+
+    #[test]
+    fn simplify() {
+        let mut regex = Regex::new();
+        let empty = regex.push(Op::Empty);
+        let e = regex.push(Op::Epsilon);
+        let a = regex.push(Op::Match('a'));
+        let b = regex.push(Op::Match('b'));
+        let e_a = regex.push(Op::Concat(e, a));
+        let a_or_a = regex.push(Op::Or(e_a, a));
+        let star = regex.push(Op::Star(a_or_a));
+        let star_star = regex.push(Op::Star(star));
+        let e_or_star = regex.push(Op::Or(e, star_star));
+        let b_empty = regex.push(Op::Concat(b, empty));
+        let or_empty = regex.push(Op::Or(e_or_star, b_empty));
+        let e_star = regex.push(Op::Star(e));
+        regex.push(Op::Concat(or_empty, e_star));
+
+        let simplified = regex.simplify();
+        assert_eq!(simplified.subs, vec![Op::Match('a'), Op::Star(Handle(0))]);
+        assert_eq!(simplified.to_dfa(vec!['b']).minimized(), regex.to_dfa(vec!['b']).minimized());
+    }
+
+    #[test]
+    fn simplify_star() {
+        let simplify = |input| Printer::default().print(&Regex::parse(input).unwrap().simplify());
+
+        assert_eq!(simplify("(()|a|b*)*"), "(a|b)*");
+        assert_eq!(simplify("(aa*)*"), "a*");
+        assert_eq!(simplify("a*a*|a"), "a*");
+        assert_eq!(simplify("[]*"), "()");
+        assert_eq!(simplify("a[]|[]"), "[]");
+        assert_eq!(simplify("(()|a*)b|()"), "(a*b)?");
+    }
+
+    #[test]
+    fn invalid_handle() {
+        let mut regex = Regex::new();
+        let a = regex.try_push(Op::Match('a')).unwrap();
+        let star = regex.try_push(Op::Star(a)).unwrap();
+        assert_eq!(regex.try_push(Op::Concat(star, Handle(2))), Err(Error::InvalidHandle(Handle(2))));
+        assert_eq!(regex.try_push(Op::Star(Handle(5))), Err(Error::InvalidHandle(Handle(5))));
+        assert_eq!(regex.root(), Some(star));
+    }
+
+    #[test]
+    fn subexpression() {
+        let mut regex = Regex::new();
+        let a = regex.push(Op::Match('a'));
+        let b = regex.push(Op::Match('b'));
+        let star = regex.push(Op::Star(b));
+        regex.push(Op::Concat(a, star));
+
+        let sub = regex.subexpression(star);
+        assert_eq!(sub.subs, vec![Op::Match('b'), Op::Star(Handle(0))]);
+        assert_eq!(sub.root(), Some(Handle(1)));
+    }
+
+    #[test]
+    fn empty_language() {
+        let regex = Regex::<char>::new();
+        assert_eq!(regex.to_string(), "[]");
+        assert_eq!(Printer::default().print(&regex), "[]");
+        assert!(!regex.to_nfa().contains("".chars()));
+        assert!(!regex.to_position_nfa().contains("".chars()));
+        assert!(!regex.to_dfa(vec!['a']).contains("".chars()));
+
+        let mut regex = Regex::new();
+        let a = regex.push(Op::Match('a'));
+        let empty = regex.push(Op::Empty);
+        let star = regex.push(Op::Star(empty));
+        regex.push(Op::Concat(a, star));
+
+        assert!(regex.to_nfa().contains("a".chars()));
+        assert!(regex.to_position_nfa().contains("a".chars()));
+        assert!(regex.to_dfa(vec![]).contains("a".chars()));
+        assert!(!regex.to_dfa(vec![]).contains("aa".chars()));
+    }
+}
@@ -0,0 +1,394 @@
+//! A parser for the textual representation of regexes over `char`.
+//!
+//! The syntax is the usual one of regular expressions, with alternatives `a|b`, concatenation,
+//! the postfix operators `*`, `+` and `?`, grouping with parentheses and character classes with
+//! ranges such as `[a-z0-9]`, where a `-` at the start or end of a class is literal. The empty
+//! word is written as `()` or `{e}`, the empty class `[]` matches nothing at all. Any ASCII
+//! punctuation, including the meta characters `|*+?()[]{}\`, is matched literally when escaped
+//! with a backslash, the escapes `\n`, `\r`, `\t` and `\0` denote the respective control
+//! characters.
+//!
+//! Additionally, a symbol can be given as a quoted character literal in braces, for example
+//! `{'a'}` or `{'\u{301}'}`. Together with `{e}` this parses the output of `Regex::to_string`.
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Cached, Handle, Op, Regex};
+
+/// An error encountered while parsing a regex.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The byte offset in the input at which the error was detected.
+    pub offset: usize,
+
+    /// The kind of error.
+    pub kind: ParseErrorKind,
+}
+
+/// The kinds of errors in the textual representation of a regex.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// The input ended in the middle of an expression.
+    UnexpectedEnd,
+
+    /// A character that can not appear at this point.
+    UnexpectedChar(char),
+
+    /// A backslash followed by a character without escape meaning.
+    InvalidEscape(char),
+
+    /// A character range whose end is smaller than its start.
+    InvalidRange(char, char),
+
+    /// A negated character class, which requires knowing the whole alphabet.
+    NegatedClass,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    offset: usize,
+    regex: Cached<char>,
+}
+
+impl Regex<char> {
+    /// Parse a regex from its textual representation.
+    ///
+    /// See the module documentation of `regex::parse` for the syntax. Every string printed by
+    /// `to_string` is parsed into a regex of the same language.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            input,
+            offset: 0,
+            regex: Cached::new(),
+        };
+
+        let root = parser.alternative()?;
+        if let Some(ch) = parser.peek() {
+            return Err(parser.error(ParseErrorKind::UnexpectedChar(ch)));
+        }
+
+        let regex = parser.regex.into_inner();
+        debug_assert_eq!(regex.root(), Some(root), "The outermost operation is inserted last");
+        Ok(regex)
+    }
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.offset..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.offset += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn next(&mut self) -> Result<char, ParseError> {
+        self.bump().ok_or_else(|| self.error(ParseErrorKind::UnexpectedEnd))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        let offset = self.offset;
+        match self.next()? {
+            ch if ch == expected => Ok(()),
+            ch => Err(ParseError { offset, kind: ParseErrorKind::UnexpectedChar(ch) }),
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        ParseError {
+            offset: self.offset,
+            kind,
+        }
+    }
+
+    /// `alternative := concat ('|' concat)*`
+    fn alternative(&mut self) -> Result<Handle, ParseError> {
+        let mut alternative = self.concat()?;
+        while self.peek() == Some('|') {
+            self.bump();
+            let next = self.concat()?;
+            alternative = self.regex.insert(Op::Or(alternative, next));
+        }
+        Ok(alternative)
+    }
+
+    /// `concat := repeat*`, where the empty concatenation is the empty word.
+    fn concat(&mut self) -> Result<Handle, ParseError> {
+        let mut concat = None;
+        loop {
+            match self.peek() {
+                None | Some('|') | Some(')') => break,
+                Some(_) => (),
+            }
+
+            let next = self.repeat()?;
+            concat = Some(match concat {
+                None => next,
+                Some(concat) => self.regex.insert(Op::Concat(concat, next)),
+            });
+        }
+        Ok(concat.unwrap_or_else(|| self.regex.insert(Op::Epsilon)))
+    }
+
+    /// `repeat := atom ('*' | '+' | '?')*`
+    fn repeat(&mut self) -> Result<Handle, ParseError> {
+        let mut repeat = self.atom()?;
+        loop {
+            repeat = match self.peek() {
+                Some('*') => self.regex.insert(Op::Star(repeat)),
+                Some('+') => {
+                    let star = self.regex.insert(Op::Star(repeat));
+                    self.regex.insert(Op::Concat(repeat, star))
+                },
+                Some('?') => {
+                    let epsilon = self.regex.insert(Op::Epsilon);
+                    self.regex.insert(Op::Or(epsilon, repeat))
+                },
+                _ => return Ok(repeat),
+            };
+            self.bump();
+        }
+    }
+
+    /// `atom := '(' alternative ')' | '[' class ']' | '{' braced '}' | escape | literal`
+    fn atom(&mut self) -> Result<Handle, ParseError> {
+        let offset = self.offset;
+        match self.next()? {
+            '(' => {
+                let inner = self.alternative()?;
+                self.expect(')')?;
+                Ok(inner)
+            },
+            '[' => self.class(),
+            '{' => {
+                let inner = self.braced()?;
+                self.expect('}')?;
+                Ok(inner)
+            },
+            '\\' => {
+                let ch = self.escape()?;
+                Ok(self.regex.insert(Op::Match(ch)))
+            },
+            ch if is_meta(ch) => Err(ParseError { offset, kind: ParseErrorKind::UnexpectedChar(ch) }),
+            ch => Ok(self.regex.insert(Op::Match(ch))),
+        }
+    }
+
+    /// The character after a backslash.
+    fn escape(&mut self) -> Result<char, ParseError> {
+        let offset = self.offset;
+        match self.next()? {
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            '0' => Ok('\0'),
+            ch if ch.is_ascii_punctuation() => Ok(ch),
+            ch => Err(ParseError { offset, kind: ParseErrorKind::InvalidEscape(ch) }),
+        }
+    }
+
+    /// A character class, after the opening bracket.
+    fn class(&mut self) -> Result<Handle, ParseError> {
+        if self.peek() == Some('^') {
+            return Err(self.error(ParseErrorKind::NegatedClass));
+        }
+
+        let mut members = Vec::new();
+        loop {
+            let offset = self.offset;
+            let start = match self.next()? {
+                ']' => break,
+                '\\' => self.escape()?,
+                ch => ch,
+            };
+
+            // A `-` right before the closing bracket is literal, like one right after the opening.
+            if self.peek() != Some('-') || self.input[self.offset..].starts_with("-]") {
+                members.push(start);
+                continue;
+            }
+
+            self.bump();
+            let end = match self.next()? {
+                '\\' => self.escape()?,
+                ch => ch,
+            };
+
+            if end < start {
+                return Err(ParseError { offset, kind: ParseErrorKind::InvalidRange(start, end) });
+            }
+            members.extend(start..=end);
+        }
+
+        members.sort();
+        members.dedup();
+        let members = members.into_iter()
+            .map(|ch| self.regex.insert(Op::Match(ch)))
+            .collect::<Vec<_>>();
+        Ok(match members.split_first() {
+            None => self.regex.insert(Op::Empty),
+            Some((&first, rest)) => rest.iter()
+                .fold(first, |class, &ch| self.regex.insert(Op::Or(class, ch))),
+        })
+    }
+
+    /// Either `e` for the empty word or a quoted character literal, after the opening brace.
+    fn braced(&mut self) -> Result<Handle, ParseError> {
+        let offset = self.offset;
+        match self.next()? {
+            'e' => Ok(self.regex.insert(Op::Epsilon)),
+            '\'' => {
+                let ch = self.char_literal()?;
+                self.expect('\'')?;
+                Ok(self.regex.insert(Op::Match(ch)))
+            },
+            ch => Err(ParseError { offset, kind: ParseErrorKind::UnexpectedChar(ch) }),
+        }
+    }
+
+    /// The contents of a character literal, escaped as by `char::escape_debug`.
+    fn char_literal(&mut self) -> Result<char, ParseError> {
+        match self.next()? {
+            '\\' => (),
+            ch => return Ok(ch),
+        }
+
+        let offset = self.offset;
+        match self.next()? {
+            'n' => Ok('\n'),
+            'r' => Ok('\r'),
+            't' => Ok('\t'),
+            '0' => Ok('\0'),
+            ch @ '\\' | ch @ '\'' | ch @ '"' => Ok(ch),
+            'u' => {
+                self.expect('{')?;
+                let start = self.offset;
+                while self.peek().is_some_and(|ch| ch.is_ascii_hexdigit()) {
+                    self.bump();
+                }
+                let digits = &self.input[start..self.offset];
+                let ch = u32::from_str_radix(digits, 16).ok()
+                    .and_then(std::char::from_u32)
+                    .ok_or(ParseError { offset, kind: ParseErrorKind::InvalidEscape('u') })?;
+                self.expect('}')?;
+                Ok(ch)
+            },
+            ch => Err(ParseError { offset, kind: ParseErrorKind::InvalidEscape(ch) }),
+        }
+    }
+}
+
+/// Characters with a special meaning, which need to be escaped to be matched literally.
+fn is_meta(ch: char) -> bool {
+    "|*+?()[]{}\\".contains(ch)
+}
+
+impl FromStr for Regex<char> {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        Regex::parse(input)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at byte offset {}", self.kind, self.offset)
+    }
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseErrorKind::UnexpectedChar(ch) => write!(f, "unexpected character {:?}", ch),
+            ParseErrorKind::InvalidEscape(ch) => write!(f, "invalid escape sequence \\{}", ch),
+            ParseErrorKind::InvalidRange(start, end) =>
+                write!(f, "invalid character range {:?}-{:?}", start, end),
+            ParseErrorKind::NegatedClass => write!(f, "negated character classes are not supported"),
+        }
+    }
+}
+
+impl Error for ParseError { }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_same(left: &Regex<char>, right: &Regex<char>) {
+        let alphabet = left.to_dfa(vec![]).alphabet().to_vec();
+        assert_eq!(
+            left.to_dfa(alphabet.iter().cloned()).minimized(),
+            right.to_dfa(alphabet.iter().cloned()).minimized());
+    }
+
+    #[test]
+    fn parse() {
+        let regex = Regex::parse("(a|b)*abb").unwrap();
+        let automaton = regex.to_dfa(vec![]);
+        assert!( automaton.contains("abb".chars()));
+        assert!( automaton.contains("babb".chars()));
+        assert!(!automaton.contains("abba".chars()));
+
+        let regex = Regex::parse("[a-c]+x?|()").unwrap();
+        let automaton = regex.to_dfa(vec![]);
+        assert!( automaton.contains("".chars()));
+        assert!( automaton.contains("cab".chars()));
+        assert!( automaton.contains("bx".chars()));
+        assert!(!automaton.contains("x".chars()));
+
+        let regex = Regex::parse("a|[]").unwrap();
+        let automaton = regex.to_dfa(vec![]);
+        assert!( automaton.contains("a".chars()));
+        assert!(!automaton.contains("".chars()));
+
+        let regex = Regex::parse(r"\*\n{'\''}").unwrap();
+        let automaton = regex.to_dfa(vec![]);
+        assert!( automaton.contains("*\n'".chars()));
+
+        let regex = Regex::parse(r"\.[\^a\-c]\&").unwrap();
+        let automaton = regex.to_dfa(vec![]);
+        assert!( automaton.contains(".^&".chars()));
+        assert!( automaton.contains(".-&".chars()));
+        assert!( automaton.contains(".c&".chars()));
+        assert!(!automaton.contains(".b&".chars()));
+        assert!(!automaton.contains("x^&".chars()));
+
+        for input in ["[a-]", "[-a]"].iter() {
+            let automaton = Regex::parse(input).unwrap().to_dfa(vec![]);
+            assert!( automaton.contains("a".chars()), "{}", input);
+            assert!( automaton.contains("-".chars()), "{}", input);
+            assert!(!automaton.contains("".chars()), "{}", input);
+        }
+
+        let automaton = Regex::parse("[-]").unwrap().to_dfa(vec![]);
+        assert!(automaton.contains("-".chars()));
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in ["(a|b)*abb", "a+b?|[x-z]*", "{e}", "\\(\\)|{'\\u{301}'}", "a||b", "[]*|b[]"].iter() {
+            let regex = Regex::parse(input).unwrap();
+            let printed = Regex::parse(&regex.to_string()).unwrap();
+            assert_same(&regex, &printed);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let error = |input| Regex::parse(input).err().unwrap();
+
+        assert_eq!(error("(ab"), ParseError { offset: 3, kind: ParseErrorKind::UnexpectedEnd });
+        assert_eq!(error("ab)"), ParseError { offset: 2, kind: ParseErrorKind::UnexpectedChar(')') });
+        assert_eq!(error("a|*"), ParseError { offset: 2, kind: ParseErrorKind::UnexpectedChar('*') });
+        assert_eq!(error("a\\q"), ParseError { offset: 2, kind: ParseErrorKind::InvalidEscape('q') });
+        assert_eq!(error("[z-a]"), ParseError { offset: 1, kind: ParseErrorKind::InvalidRange('z', 'a') });
+        assert_eq!(error("ab[a-cz-a]"), ParseError { offset: 6, kind: ParseErrorKind::InvalidRange('z', 'a') });
+        assert_eq!(error("ä[a-"), ParseError { offset: 5, kind: ParseErrorKind::UnexpectedEnd });
+        assert_eq!(error("[^a]"), ParseError { offset: 1, kind: ParseErrorKind::NegatedClass });
+    }
+}
@@ -0,0 +1,303 @@
+//! A configurable printer producing readable regexes.
+//!
+//! Contrary to `Regex::to_string`, which puts every symbol and operation into braces and
+//! parentheses, the printer only inserts parentheses where the precedence of operations requires
+//! them. Stars bind strongest, then concatenation, then alternatives.
+use std::fmt::Display;
+
+use crate::Alphabet;
+use super::{Handle, Op, Regex};
+
+/// The flavour of regular expression syntax to print.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Syntax {
+    /// The syntax accepted by `Regex::parse`.
+    Native,
+
+    /// POSIX extended regular expressions, as used by `grep -E`.
+    ///
+    /// The empty word is printed as `()`, which most but not all implementations accept. The empty
+    /// language is printed as `.^`, relying on anchors being allowed anywhere.
+    PosixExtended,
+
+    /// The syntax of the `regex` crate.
+    Rust,
+}
+
+/// Prints regexes with minimal parentheses.
+///
+/// Symbols are printed with their `Display` representation, or with a custom function, and
+/// escaped where they would be interpreted as operators. Symbols whose representation is longer
+/// than a single character are grouped so that operators apply to them as a whole.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Printer {
+    syntax: Syntax,
+    collapse: bool,
+}
+
+/// Binding strength of the printed operations, from weakest to strongest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Precedence {
+    Or,
+    Concat,
+    Postfix,
+    Atom,
+}
+
+impl Printer {
+    /// A printer for the given syntax, which collapses repetitions and options.
+    pub fn new(syntax: Syntax) -> Self {
+        Printer {
+            syntax,
+            collapse: true,
+        }
+    }
+
+    /// Configure whether `xx*` is printed as `x+` and `(|x)` as `x?`.
+    ///
+    /// This is enabled by default. The collapsed terms are detected by identical handles, so
+    /// subexpressions should be deduplicated, for example by constructing them with `Cached`.
+    pub fn collapse(self, collapse: bool) -> Self {
+        Printer {
+            collapse,
+            .. self
+        }
+    }
+
+    /// Print the regex, using the `Display` representation of its symbols.
+    ///
+    /// A regex without a root is printed as the empty language.
+    pub fn print<A>(&self, regex: &Regex<A>) -> String
+        where A: Alphabet + Display
+    {
+        self.print_with(regex, |symbol| symbol.to_string())
+    }
+
+    /// Print the regex, representing symbols with a custom function.
+    ///
+    /// A regex without a root is printed as the empty language.
+    pub fn print_with<A, F>(&self, regex: &Regex<A>, mut symbol: F) -> String
+        where A: Alphabet, F: FnMut(&A) -> String
+    {
+        let root = match regex.root() {
+            Some(root) => root,
+            None => return self.empty().to_string(),
+        };
+        let mut output = String::new();
+        self.write(regex, root, Precedence::Or, &mut symbol, &mut output);
+        output
+    }
+
+    fn write<A, F>(
+        &self,
+        regex: &Regex<A>,
+        handle: Handle,
+        context: Precedence,
+        symbol: &mut F,
+        output: &mut String,
+    )
+        where A: Alphabet, F: FnMut(&A) -> String
+    {
+        let precedence = self.precedence(regex, handle);
+        if precedence < context {
+            output.push_str(self.open_group());
+            self.write_op(regex, handle, symbol, output);
+            output.push(')');
+        } else {
+            self.write_op(regex, handle, symbol, output);
+        }
+    }
+
+    fn write_op<A, F>(&self, regex: &Regex<A>, handle: Handle, symbol: &mut F, output: &mut String)
+        where A: Alphabet, F: FnMut(&A) -> String
+    {
+        if let Some((repeated, postfix)) = self.collapsed(regex, handle) {
+            self.write(regex, repeated, Precedence::Atom, symbol, output);
+            output.push(postfix);
+            return;
+        }
+
+        match regex.subs[handle.0] {
+            Op::Empty => output.push_str(self.empty()),
+            Op::Epsilon => {
+                output.push_str(self.open_group());
+                output.push(')');
+            },
+            Op::Match(ref a) => {
+                let text = symbol(a);
+                let grouped = text.chars().count() != 1;
+                if grouped {
+                    output.push_str(self.open_group());
+                }
+                text.chars().for_each(|ch| self.escape(ch, output));
+                if grouped {
+                    output.push(')');
+                }
+            },
+            Op::Star(sub) => {
+                self.write(regex, sub, Precedence::Atom, symbol, output);
+                output.push('*');
+            },
+            Op::Or(a, b) => {
+                self.write(regex, a, Precedence::Or, symbol, output);
+                output.push('|');
+                self.write(regex, b, Precedence::Or, symbol, output);
+            },
+            Op::Concat(a, b) => {
+                self.write(regex, a, Precedence::Concat, symbol, output);
+                self.write(regex, b, Precedence::Concat, symbol, output);
+            },
+        }
+    }
+
+    fn precedence<A: Alphabet>(&self, regex: &Regex<A>, handle: Handle) -> Precedence {
+        if self.collapsed(regex, handle).is_some() {
+            return Precedence::Postfix;
+        }
+
+        match regex.subs[handle.0] {
+            // Either printed as a group or grouped when it is not a single character.
+            Op::Epsilon | Op::Match(_) => Precedence::Atom,
+            Op::Empty if self.syntax == Syntax::PosixExtended => Precedence::Concat,
+            Op::Empty => Precedence::Atom,
+            Op::Star(_) => Precedence::Postfix,
+            Op::Or(_, _) => Precedence::Or,
+            Op::Concat(_, _) => Precedence::Concat,
+        }
+    }
+
+    /// Detect `xx*`, `x*x` as `x+` and `(|x)`, `(x|)` as `x?`.
+    fn collapsed<A: Alphabet>(&self, regex: &Regex<A>, handle: Handle) -> Option<(Handle, char)> {
+        if !self.collapse {
+            return None;
+        }
+
+        let is_star_of = |star: Handle, sub: Handle| regex.subs[star.0] == Op::Star(sub);
+        let is_epsilon = |handle: Handle| regex.subs[handle.0] == Op::Epsilon;
+
+        match regex.subs[handle.0] {
+            Op::Concat(a, b) if is_star_of(b, a) => Some((a, '+')),
+            Op::Concat(a, b) if is_star_of(a, b) => Some((b, '+')),
+            Op::Or(a, b) if is_epsilon(a) && !is_epsilon(b) => Some((b, '?')),
+            Op::Or(a, b) if is_epsilon(b) && !is_epsilon(a) => Some((a, '?')),
+            _ => None,
+        }
+    }
+
+    fn open_group(&self) -> &'static str {
+        match self.syntax {
+            Syntax::Native | Syntax::PosixExtended => "(",
+            Syntax::Rust => "(?:",
+        }
+    }
+
+    /// A regex matching no word at all.
+    fn empty(&self) -> &'static str {
+        match self.syntax {
+            Syntax::Native => "[]",
+            // There are no empty bracket expressions, but nothing precedes the start of a line.
+            Syntax::PosixExtended => ".^",
+            Syntax::Rust => "[^\\s\\S]",
+        }
+    }
+
+    fn escape(&self, ch: char, output: &mut String) {
+        let meta = match self.syntax {
+            Syntax::Native => "|*+?()[]{}\\",
+            Syntax::PosixExtended => ".[]()*+?{}|^$\\",
+            Syntax::Rust => ".+*?()|[]{}^$#&-~\\",
+        };
+
+        match ch {
+            '\n' if self.syntax != Syntax::PosixExtended => output.push_str("\\n"),
+            '\r' if self.syntax != Syntax::PosixExtended => output.push_str("\\r"),
+            '\t' if self.syntax != Syntax::PosixExtended => output.push_str("\\t"),
+            '\0' if self.syntax == Syntax::Native => output.push_str("\\0"),
+            ch if meta.contains(ch) => {
+                output.push('\\');
+                output.push(ch);
+            },
+            ch => output.push(ch),
+        }
+    }
+}
+
+impl Default for Printer {
+    fn default() -> Self {
+        Printer::new(Syntax::Native)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_parentheses() {
+        let printer = Printer::default();
+        let print = |input| printer.print(&Regex::parse(input).unwrap());
+
+        assert_eq!(print("(a|b)*abb"), "(a|b)*abb");
+        assert_eq!(print("((a)(b))|(c*)"), "ab|c*");
+        assert_eq!(print("(ab)*"), "(ab)*");
+        assert_eq!(print("a(b|c)d"), "a(b|c)d");
+        assert_eq!(print("{'a'}{e}"), "a()");
+        assert_eq!(print("\\*\\|"), "\\*\\|");
+    }
+
+    #[test]
+    fn collapse() {
+        let printer = Printer::default();
+        let print = |input| printer.print(&Regex::parse(input).unwrap());
+
+        assert_eq!(print("aa*"), "a+");
+        assert_eq!(print("(ab)(ab)*"), "(ab)+");
+        assert_eq!(print("{e}|a"), "a?");
+        assert_eq!(print("(a|b)?c+"), "(a|b)?c+");
+        assert_eq!(print("(a?)*"), "(a?)*");
+
+        let printer = printer.collapse(false);
+        assert_eq!(printer.print(&Regex::parse("a+|b?").unwrap()), "aa*|()|b");
+    }
+
+    #[test]
+    fn syntaxes() {
+        let regex = Regex::parse("(a.b|{e})+").unwrap();
+        assert_eq!(Printer::new(Syntax::Native).print(&regex), "((a.b)?)+");
+        assert_eq!(Printer::new(Syntax::PosixExtended).print(&regex), "((a\\.b)?)+");
+        assert_eq!(Printer::new(Syntax::Rust).print(&regex), "(?:(?:a\\.b)?)+");
+    }
+
+    #[test]
+    fn empty() {
+        let regex = Regex::parse("[]|a[]*").unwrap();
+        assert_eq!(Printer::new(Syntax::Native).print(&regex), "[]|a[]*");
+        assert_eq!(Printer::new(Syntax::PosixExtended).print(&regex), ".^|a(.^)*");
+        assert_eq!(Printer::new(Syntax::Rust).print(&regex), "[^\\s\\S]|a[^\\s\\S]*");
+        assert_eq!(Printer::default().print(&Regex::<char>::new()), "[]");
+    }
+
+    #[test]
+    fn symbols() {
+        let mut regex = Regex::new();
+        let ten = regex.push(Op::Match(10));
+        let one = regex.push(Op::Match(1));
+        let star = regex.push(Op::Star(ten));
+        regex.push(Op::Concat(one, star));
+
+        assert_eq!(Printer::default().print(&regex), "1(10)*");
+        assert_eq!(Printer::default().print_with(&regex, |x| format!("<{}>", x)), "(<1>)(<10>)*");
+    }
+
+    #[test]
+    fn round_trip() {
+        let printer = Printer::default();
+        for input in ["(a|b)*abb", "a+b?|[x-z]*", "(|a)(b|)", "\\(\\)|{'\\n'}"].iter() {
+            let regex = Regex::parse(input).unwrap();
+            let printed = Regex::parse(&printer.print(&regex)).unwrap();
+            assert_eq!(
+                regex.to_dfa(vec![]).minimized(),
+                printed.to_dfa(regex.to_dfa(vec![]).alphabet().iter().cloned()).minimized());
+        }
+    }
+}
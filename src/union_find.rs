@@ -0,0 +1,70 @@
+//! Disjoint sets of dense indices.
+//!
+//! Sets are trees of parent pointers, unioned by rank and compressed by path halving during each
+//! lookup. A sequence of operations then costs time nearly linear in its length, the extra factor
+//! being the inverse Ackermann function.
+pub struct UnionFind {
+    /// The parent of each element, roots are their own parent.
+    parent: Vec<usize>,
+
+    /// An upper bound on the height of the tree below each root.
+    rank: Vec<u8>,
+}
+
+impl UnionFind {
+    /// Create `len` singleton sets.
+    pub fn new(len: usize) -> Self {
+        UnionFind {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+        }
+    }
+
+    /// The representative of the set containing an element.
+    pub fn find(&mut self, mut element: usize) -> usize {
+        while self.parent[element] != element {
+            let grandparent = self.parent[self.parent[element]];
+            self.parent[element] = grandparent;
+            element = grandparent;
+        }
+        element
+    }
+
+    /// Join the sets of two elements.
+    ///
+    /// Returns `false` if they were already in the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (lower, upper) = if self.rank[a] < self.rank[b] { (a, b) } else { (b, a) };
+        self.parent[lower] = upper;
+        if self.rank[lower] == self.rank[upper] {
+            self.rank[upper] += 1;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn union_sets() {
+        let mut sets = UnionFind::new(5);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(3, 4));
+        assert!(!sets.union(1, 0));
+
+        assert_eq!(sets.find(0), sets.find(1));
+        assert_ne!(sets.find(1), sets.find(3));
+        assert_ne!(sets.find(2), sets.find(4));
+
+        assert!(sets.union(1, 4));
+        assert_eq!(sets.find(0), sets.find(3));
+        assert!(!sets.union(0, 4));
+    }
+}